@@ -1,16 +1,28 @@
-use devise::{FromMeta, Result, SpanWrapped, Diagnostic};
+use devise::{FromMeta, Result, Spanned, SpanWrapped, Diagnostic};
 use devise::ext::SpanDiagnosticExt;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, quote_spanned};
 use syn::{Attribute, parse::Parser};
 
 use crate::{
+    AFTER_ALL_HOOK_FN_LOCATION_FN_PREFIX,
+    AFTER_ALL_HOOK_FN_PREFIX,
+    AFTER_ALL_HOOK_STRUCT_PREFIX,
+    AFTER_FEATURE_HOOK_FN_LOCATION_FN_PREFIX,
+    AFTER_FEATURE_HOOK_FN_PREFIX,
+    AFTER_FEATURE_HOOK_STRUCT_PREFIX,
     AFTER_SCENARIO_HOOK_FN_LOCATION_FN_PREFIX,
     AFTER_SCENARIO_HOOK_FN_PREFIX,
     AFTER_SCENARIO_HOOK_STRUCT_PREFIX,
     AFTER_STEP_HOOK_FN_LOCATION_FN_PREFIX,
     AFTER_STEP_HOOK_FN_PREFIX,
     AFTER_STEP_HOOK_STRUCT_PREFIX,
+    BEFORE_ALL_HOOK_FN_LOCATION_FN_PREFIX,
+    BEFORE_ALL_HOOK_FN_PREFIX,
+    BEFORE_ALL_HOOK_STRUCT_PREFIX,
+    BEFORE_FEATURE_HOOK_FN_LOCATION_FN_PREFIX,
+    BEFORE_FEATURE_HOOK_FN_PREFIX,
+    BEFORE_FEATURE_HOOK_STRUCT_PREFIX,
     BEFORE_SCENARIO_HOOK_FN_LOCATION_FN_PREFIX,
     BEFORE_SCENARIO_HOOK_FN_PREFIX,
     BEFORE_SCENARIO_HOOK_STRUCT_PREFIX,
@@ -19,7 +31,7 @@ use crate::{
     BEFORE_STEP_HOOK_STRUCT_PREFIX,
 };
 use crate::attribute::GlueFnArg;
-use crate::glue_codegen::{HookType, TagExpression};
+use crate::glue_codegen::{HookType, Optional, TagExpression, Timeout};
 use crate::proc_macro_ext::{Diagnostics, StringLit};
 use crate::syn_ext::IdentExt;
 
@@ -30,6 +42,7 @@ struct HookAttribute {
     hook_type: SpanWrapped<HookType>,
     order: Option<isize>,
     tag_expression: Option<TagExpression>,
+    timeout: Option<Timeout>,
 }
 
 /// The raw, parsed `#[hook]` (e.g, `before_scenario`, `before_step`, ...) attribute.
@@ -37,6 +50,7 @@ struct HookAttribute {
 struct HookTypeHookAttribute {
     order: Option<isize>,
     tag_expression: Option<TagExpression>,
+    timeout: Option<Timeout>,
 }
 
 /// This structure represents the parsed `hook` attribute and associated items.
@@ -63,10 +77,14 @@ fn generate_location_fn_name(user_handler_fn_name: &Ident, hook_type: &HookType)
     use crate::glue::hook::HookType::*;
 
     let hook_fn_location_fn_prefix = match hook_type.0 {
+        BeforeAll => BEFORE_ALL_HOOK_FN_LOCATION_FN_PREFIX,
+        BeforeFeature => BEFORE_FEATURE_HOOK_FN_LOCATION_FN_PREFIX,
         BeforeScenario => BEFORE_SCENARIO_HOOK_FN_LOCATION_FN_PREFIX,
         BeforeStep => BEFORE_STEP_HOOK_FN_LOCATION_FN_PREFIX,
         AfterStep => AFTER_STEP_HOOK_FN_LOCATION_FN_PREFIX,
         AfterScenario => AFTER_SCENARIO_HOOK_FN_LOCATION_FN_PREFIX,
+        AfterFeature => AFTER_FEATURE_HOOK_FN_LOCATION_FN_PREFIX,
+        AfterAll => AFTER_ALL_HOOK_FN_LOCATION_FN_PREFIX,
     };
 
     user_handler_fn_name.prepend(hook_fn_location_fn_prefix)
@@ -76,10 +94,14 @@ fn generate_fn_name(user_handler_fn_name: &Ident, hook_type: &HookType) -> Ident
     use crate::glue::hook::HookType::*;
 
     let hook_fn_prefix = match hook_type.0 {
+        BeforeAll => BEFORE_ALL_HOOK_FN_PREFIX,
+        BeforeFeature => BEFORE_FEATURE_HOOK_FN_PREFIX,
         BeforeScenario => BEFORE_SCENARIO_HOOK_FN_PREFIX,
         BeforeStep => BEFORE_STEP_HOOK_FN_PREFIX,
         AfterStep => AFTER_STEP_HOOK_FN_PREFIX,
         AfterScenario => AFTER_SCENARIO_HOOK_FN_PREFIX,
+        AfterFeature => AFTER_FEATURE_HOOK_FN_PREFIX,
+        AfterAll => AFTER_ALL_HOOK_FN_PREFIX,
     };
 
     user_handler_fn_name.prepend(hook_fn_prefix)
@@ -89,10 +111,14 @@ fn generate_struct_name(user_handler_fn_name: &Ident, hook_type: &HookType) -> I
     use crate::glue::hook::HookType::*;
 
     let hook_struct_prefix = match hook_type.0 {
+        BeforeAll => BEFORE_ALL_HOOK_STRUCT_PREFIX,
+        BeforeFeature => BEFORE_FEATURE_HOOK_STRUCT_PREFIX,
         BeforeScenario => BEFORE_SCENARIO_HOOK_STRUCT_PREFIX,
         BeforeStep => BEFORE_STEP_HOOK_STRUCT_PREFIX,
         AfterStep => AFTER_STEP_HOOK_STRUCT_PREFIX,
         AfterScenario => AFTER_SCENARIO_HOOK_STRUCT_PREFIX,
+        AfterFeature => AFTER_FEATURE_HOOK_STRUCT_PREFIX,
+        AfterAll => AFTER_ALL_HOOK_STRUCT_PREFIX,
     };
 
     user_handler_fn_name.prepend(hook_struct_prefix)
@@ -103,22 +129,47 @@ fn codegen_hook(hook: Hook) -> Result<TokenStream> {
     let (vis, user_handler_fn) = (&hook.function.vis, &hook.function);
     let user_handler_fn_name = &user_handler_fn.sig.ident;
     let user_handler_fn_span = user_handler_fn.sig.ident.span();
+    let user_handler_fn_output_span = user_handler_fn.sig.output.span();
     let hook_type = hook.attribute.hook_type;
     let generated_location_fn_name = generate_location_fn_name(user_handler_fn_name, &hook_type.value);
     let generated_fn_name = generate_fn_name(user_handler_fn_name, &hook_type.value);
     let generated_struct_name = generate_struct_name(user_handler_fn_name, &hook_type.value);
     let parameter_names = hook.arguments.iter().map(|argument| &argument.cuke_runner_ident);
     let order = hook.attribute.order.unwrap_or(0);
-    let tag_expression = hook.attribute.tag_expression
-        .map(|t| t.0)
-        .unwrap_or_else(String::new);
+    let global = hook_type.value.0.is_global();
+
+    if global && hook.attribute.tag_expression.is_some() {
+        return Err(hook_type.span.error("global hooks cannot be restricted by a tag expression")
+            .help("global hooks are executed once per test run, not per scenario"));
+    }
+
+    let feature = hook_type.value.0.is_feature();
+
+    if (global || feature) && hook.attribute.timeout.is_some() {
+        return Err(hook_type.span.error("global and feature hooks cannot have a timeout")
+            .help("timeouts are only supported for scenario and step hooks"));
+    }
 
     let mut data_statements = Vec::with_capacity(hook.arguments.len());
     for argument in &hook.arguments {
-        let data_statement = super::scenario_data_expr(&argument)?;
+        let data_statement = if global {
+            hook_data_expr(&argument, quote!(__global), "global hooks",
+                "use `&mut Global` or `&Global` to access the data shared by all scenarios")?
+        } else if feature {
+            hook_data_expr(&argument, quote!(__feature), "feature hooks",
+                "use `&mut Feature` or `&Feature` to access the data shared by the scenarios of a feature")?
+        } else {
+            super::scenario_data_expr(&argument)?
+        };
         data_statements.push(data_statement);
     }
 
+    // spanned to the return type so that an unsupported return type
+    // is reported at the user handler function signature
+    let into_execution_result = quote_spanned! {user_handler_fn_output_span=>
+        ::cuke_runner::glue::error::IntoExecutionResult::into_execution_result(user_handler_fn_result)
+    };
+
     // quote_spanned so that the line information points
     // to the user handler function in the source file
     // instead of the macro invocation line
@@ -133,6 +184,58 @@ fn codegen_hook(hook: Hook) -> Result<TokenStream> {
         }
     };
 
+    let generated_fn_param = if global {
+        quote!(__global: &mut ::cuke_runner::glue::global::Global)
+    } else if feature {
+        quote!(__feature: &mut ::cuke_runner::glue::feature::Feature)
+    } else {
+        quote!(__scenario: &mut ::cuke_runner::glue::scenario::Scenario)
+    };
+
+    let static_hook_def = if global {
+        quote! {
+            #vis static #generated_struct_name: ::cuke_runner::glue::hook::StaticGlobalHookDef =
+                ::cuke_runner::glue::hook::StaticGlobalHookDef {
+                    name: stringify!(#user_handler_fn_name),
+                    order: #order,
+                    hook_fn: #generated_fn_name,
+                    hook_fn_location_fn: #generated_location_fn_name,
+                };
+        }
+    } else if feature {
+        let tag_expression = hook.attribute.tag_expression
+            .map(|t| t.0)
+            .unwrap_or_else(String::new);
+
+        quote! {
+            #vis static #generated_struct_name: ::cuke_runner::glue::hook::StaticFeatureHookDef =
+                ::cuke_runner::glue::hook::StaticFeatureHookDef {
+                    name: stringify!(#user_handler_fn_name),
+                    order: #order,
+                    tag_expression: #tag_expression,
+                    hook_fn: #generated_fn_name,
+                    hook_fn_location_fn: #generated_location_fn_name,
+                };
+        }
+    } else {
+        let tag_expression = hook.attribute.tag_expression
+            .map(|t| t.0)
+            .unwrap_or_else(String::new);
+        let timeout = Optional(hook.attribute.timeout);
+
+        quote! {
+            #vis static #generated_struct_name: ::cuke_runner::glue::hook::StaticHookDef =
+                ::cuke_runner::glue::hook::StaticHookDef {
+                    name: stringify!(#user_handler_fn_name),
+                    order: #order,
+                    tag_expression: #tag_expression,
+                    timeout: #timeout,
+                    hook_fn: #generated_fn_name,
+                    hook_fn_location_fn: #generated_location_fn_name,
+                };
+        }
+    };
+
     Ok(quote! {
         #[inline(never)] // to see the function in the stack trace in case of a panic
         #user_handler_fn
@@ -141,32 +244,43 @@ fn codegen_hook(hook: Hook) -> Result<TokenStream> {
 
         /// Cuke runner code generated wrapping hook function.
         #vis fn #generated_fn_name(
-            __scenario: &mut ::cuke_runner::glue::scenario::Scenario,
+            #generated_fn_param
         ) -> ::std::result::Result<(), ::cuke_runner::glue::error::ExecutionError> {
 
             #(#data_statements)*
 
-            // TODO: error handling...
             let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| #user_handler_fn_name(#(#parameter_names),*)));
             match result {
-                Ok(user_handler_fn_result) => return Ok(()),
+                Ok(user_handler_fn_result) => return #into_execution_result,
                 Err(_err) => return Err(::cuke_runner::glue::error::ExecutionError::Panic(::cuke_runner::glue::error::PanicError::new())),
             };
         }
 
         /// Cuke runner code generated static hook info.
         #[allow(non_upper_case_globals)]
-        #vis static #generated_struct_name: ::cuke_runner::glue::hook::StaticHookDef =
-            ::cuke_runner::glue::hook::StaticHookDef {
-                name: stringify!(#user_handler_fn_name),
-                order: #order,
-                tag_expression: #tag_expression,
-                hook_fn: #generated_fn_name,
-                hook_fn_location_fn: #generated_location_fn_name,
-            };
+        #static_hook_def
     }.into())
 }
 
+fn hook_data_expr(argument: &GlueFnArg, data: TokenStream, hook_kind: &str, help: &str)
+    -> Result<TokenStream>
+{
+    if argument.scenario_arg {
+        return Err(argument.user_ident.span()
+            .error(format!("{} cannot take scenario data arguments", hook_kind))
+            .help(help));
+    }
+
+    let ty = &argument.ty;
+    let ident = &argument.cuke_runner_ident;
+    let span = ident.span().join(ty.span()).unwrap_or_else(|| ty.span());
+
+    Ok(quote_spanned! { span =>
+        #[allow(non_snake_case)]
+        let #ident: #ty = #data;
+    })
+}
+
 fn complete_hook(args: TokenStream, input: TokenStream) -> Result<TokenStream> {
     let function: syn::ItemFn = syn::parse2(input)
         .map_err(Diagnostic::from)
@@ -212,6 +326,7 @@ fn incomplete_hook(
         },
         order: hook_type_attribute.order,
         tag_expression: hook_type_attribute.tag_expression,
+        timeout: hook_type_attribute.timeout,
     };
 
     codegen_hook(parse_hook(attribute, function)?)
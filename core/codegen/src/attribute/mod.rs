@@ -8,6 +8,7 @@ use crate::proc_macro_ext::Diagnostics;
 use crate::syn_ext::IdentExt;
 
 pub mod hook;
+pub mod parameter_type;
 pub mod step;
 
 /// Hook or step function argument.
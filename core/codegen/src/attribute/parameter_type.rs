@@ -0,0 +1,100 @@
+use devise::{FromMeta, Result, Spanned, SpanWrapped, Diagnostic};
+use devise::ext::SpanDiagnosticExt;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{Attribute, parse::Parser};
+
+use crate::{PARAMETER_TYPE_ASSERT_FN_PREFIX, PARAMETER_TYPE_STRUCT_PREFIX};
+use crate::glue_codegen::{ParameterTypeName, ParameterTypeRegex};
+use crate::syn_ext::IdentExt;
+
+/// The raw, parsed `#[parameter_type]` attribute.
+#[derive(Debug, FromMeta)]
+struct ParameterTypeAttribute {
+    name: SpanWrapped<ParameterTypeName>,
+    regex: SpanWrapped<ParameterTypeRegex>,
+}
+
+/// This structure represents the parsed `parameter_type` attribute and associated items.
+#[derive(Debug)]
+struct ParameterType {
+    attribute: ParameterTypeAttribute,
+    /// The struct or enum that was decorated with the `parameter_type` attribute.
+    item: syn::Item,
+    vis: syn::Visibility,
+    ident: syn::Ident,
+}
+
+fn parse_parameter_type(attribute: ParameterTypeAttribute, item: syn::Item) -> Result<ParameterType> {
+    let (vis, ident, generics) = match item {
+        syn::Item::Struct(ref item_struct) => (&item_struct.vis, &item_struct.ident, &item_struct.generics),
+        syn::Item::Enum(ref item_enum) => (&item_enum.vis, &item_enum.ident, &item_enum.generics),
+        _ => {
+            return Err(item.span().error("`#[parameter_type]` can only be used on structs and enums"));
+        }
+    };
+
+    if !generics.params.is_empty() {
+        return Err(generics.span().error("parameter types cannot be generic"));
+    }
+
+    Ok(ParameterType {
+        vis: vis.clone(),
+        ident: ident.clone(),
+        attribute,
+        item,
+    })
+}
+
+fn codegen_parameter_type(parameter_type: ParameterType) -> Result<TokenStream> {
+    let (vis, item, ident) = (&parameter_type.vis, &parameter_type.item, &parameter_type.ident);
+    let generated_struct_name = ident.prepend(PARAMETER_TYPE_STRUCT_PREFIX);
+    let generated_assert_fn_name = ident.prepend(PARAMETER_TYPE_ASSERT_FN_PREFIX);
+    let name = parameter_type.attribute.name.value;
+    let regex = parameter_type.attribute.regex.value;
+
+    // spanned to the type so that a missing `FromStr` implementation is reported at the type
+    let assert_from_str = quote_spanned! {ident.span()=>
+        #[allow(dead_code, non_snake_case)]
+        fn #generated_assert_fn_name() {
+            fn assert_from_str<T: ::std::str::FromStr>() where <T as ::std::str::FromStr>::Err: ::std::fmt::Debug {}
+            assert_from_str::<#ident>();
+        }
+    };
+
+    Ok(quote! {
+        #item
+
+        #assert_from_str
+
+        /// Cuke runner code generated static parameter type info.
+        #[allow(non_upper_case_globals)]
+        #vis static #generated_struct_name: ::cuke_runner::glue::step::parameter_type::StaticParameterTypeDef =
+            ::cuke_runner::glue::step::parameter_type::StaticParameterTypeDef {
+                name: #name,
+                regex: #regex,
+                type_name: stringify!(#ident),
+            };
+    })
+}
+
+fn complete_parameter_type(args: TokenStream, input: TokenStream) -> Result<TokenStream> {
+    let item: syn::Item = syn::parse2(input)
+        .map_err(Diagnostic::from)
+        .map_err(|diag| diag.help("`#[parameter_type]` can only be used on structs and enums"))?;
+
+    let full_attr = quote!(#[parameter_type(#args)]);
+    let attrs = Attribute::parse_outer.parse2(full_attr)
+        .map_err(Diagnostic::from)?;
+    let attribute = match ParameterTypeAttribute::from_attrs("parameter_type", &attrs) {
+        Some(result) => result?,
+        None => return Err(Span::call_site().error("internal error: bad attribute"))
+    };
+
+    codegen_parameter_type(parse_parameter_type(attribute, item)?)
+}
+
+pub fn parameter_type_attribute(args: proc_macro::TokenStream, input: proc_macro::TokenStream) -> TokenStream {
+    complete_parameter_type(args.into(), input.into())
+        .unwrap_or_else(|diag| diag.emit_as_item_tokens())
+}
@@ -6,7 +6,7 @@ use quote::{quote, quote_spanned};
 
 use crate::{STEP_FN_LOCATION_FN_PREFIX, STEP_FN_PREFIX, STEP_STRUCT_PREFIX};
 use crate::attribute::GlueFnArg;
-use crate::glue_codegen::{Regex, StepKeyword};
+use crate::glue_codegen::{CompiledStepExpression, Optional, StepExpression, StepKeyword, Timeout};
 use crate::proc_macro_ext::{Diagnostics, StringLit};
 use crate::syn_ext::IdentExt;
 
@@ -15,14 +15,18 @@ use crate::syn_ext::IdentExt;
 struct StepAttribute {
     #[meta(naked)]
     keyword: SpanWrapped<StepKeyword>,
-    expression: SpanWrapped<Regex>,
+    expression: SpanWrapped<StepExpression>,
+    timeout: Option<Timeout>,
+    cucumber_expression: Option<bool>,
 }
 
 /// The raw, parsed `#[step]` (e.g, `given`, `when`, `then`) attribute.
 #[derive(Debug, FromMeta)]
 struct KeywordStepAttribute {
     #[meta(naked)]
-    expression: SpanWrapped<Regex>,
+    expression: SpanWrapped<StepExpression>,
+    timeout: Option<Timeout>,
+    cucumber_expression: Option<bool>,
 }
 
 /// This structure represents the parsed `step` attribute and associated items.
@@ -89,17 +93,146 @@ fn step_data_expr(ident: &syn::Ident, ty: &syn::Type, step_argument_index: usize
     }
 }
 
+/// Checks that the step function takes one argument for each argument captured by the step expression,
+/// optionally followed by one `&str`, `DocString` or `DataTable` argument for the doc string or data table
+/// of the step.
+fn check_step_arguments(
+    expression: &StepExpression,
+    compiled_expression: &CompiledStepExpression,
+    arguments: &[GlueFnArg],
+) -> Result<()> {
+    let step_arguments = arguments.iter()
+        .filter(|argument| !argument.scenario_arg)
+        .collect::<Vec<&GlueFnArg>>();
+    let captured_count = match compiled_expression.parameter_types {
+        Some(ref parameter_types) => parameter_types.len(),
+        // the first capture group is the implicit group of the whole match
+        None => compiled_expression.regex.captures_len() - 1,
+    };
+    let captures = match compiled_expression.parameter_types {
+        Some(_) => plural(captured_count, "parameter"),
+        None => plural(captured_count, "capture group"),
+    };
+    let takes_step_argument = step_arguments.last()
+        .map_or(false, |argument| is_doc_string_or_data_table(&argument.ty));
+    let takes_trailing_step_argument = step_arguments.get(captured_count)
+        .map_or(false, |argument| is_doc_string_or_data_table(&argument.ty) || is_str(&argument.ty));
+    let max_count = if takes_trailing_step_argument { captured_count + 1 } else { captured_count };
+    let min_count = if takes_step_argument { captured_count + 1 } else { captured_count };
+
+    if step_arguments.len() > max_count {
+        let surplus_argument = step_arguments[max_count];
+        let span = surplus_argument.user_ident.span()
+            .join(surplus_argument.ty.span())
+            .unwrap_or_else(|| surplus_argument.user_ident.span());
+
+        return Err(span.error(format!("step function takes {} but the step expression \"{}\" has {}",
+            plural(step_arguments.len(), "step argument"), expression.expression, captures))
+            .help(format!("add {} to the step expression or remove the `{}` argument, \
+                only a last `&str`, `DocString` or `DataTable` argument may take the doc string or data table \
+                of the step",
+                plural(step_arguments.len() - max_count, "capture group"), surplus_argument.user_ident)));
+    }
+
+    if step_arguments.len() < min_count {
+        let missing_count = min_count - step_arguments.len();
+        let help = if takes_step_argument {
+            format!("add {} before the doc string or data table argument \
+                or remove {} from the step expression",
+                plural(missing_count, "argument"), plural(missing_count, "capture group"))
+        } else {
+            format!("add {} like `arg{}: &str` to the step function \
+                or use non-capturing groups like `(?:...)` in the step expression",
+                plural(missing_count, "argument"), step_arguments.len() + 1)
+        };
+
+        return Err(expression.span.error(format!("step expression \"{}\" has {} but the step function takes {}",
+            expression.expression, captures, plural(step_arguments.len(), "step argument")))
+            .help(help));
+    }
+
+    Ok(())
+}
+
+/// Returns whether the type is a (reference to a) `DocString` or `DataTable`.
+fn is_doc_string_or_data_table(ty: &syn::Type) -> bool {
+    let ty = match ty {
+        syn::Type::Reference(type_reference) => &*type_reference.elem,
+        ty => ty,
+    };
+
+    match ty {
+        syn::Type::Path(type_path) => type_path.path.segments.last()
+            .map_or(false, |segment| segment.ident == "DocString" || segment.ident == "DataTable"),
+        _ => false,
+    }
+}
+
+/// Returns whether the type is `&str`, which may also take the doc string of a step.
+fn is_str(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(type_reference) => match *type_reference.elem {
+            syn::Type::Path(ref type_path) => type_path.path.is_ident("str"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// The type as it is usually written, like `&str` or `Vec<u32>`,
+/// instead of the spaced tokens of `stringify!`, like `& str` or `Vec < u32 >`.
+fn type_name(ty: &syn::Type) -> String {
+    fn is_ident_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_'
+    }
+
+    let tokens = quote!(#ty).to_string();
+    let mut name = String::with_capacity(tokens.len());
+    let mut chars = tokens.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == ' ' {
+            // only spaces between words like `dyn Trait` or `&'a str` are kept
+            let previous_char = name.chars().last();
+            let next_char = chars.peek().copied();
+            if previous_char.map_or(false, is_ident_char) && next_char.map_or(false, is_ident_char) {
+                name.push(c);
+            }
+        } else {
+            name.push(c);
+        }
+    }
+
+    name
+}
+
 fn codegen_step(step: Step) -> Result<TokenStream> {
     // Gather everything we need.
     let (vis, user_handler_fn) = (&step.function.vis, &step.function);
     let user_handler_fn_name = &user_handler_fn.sig.ident;
     let user_handler_fn_span = user_handler_fn.sig.ident.span();
+    let user_handler_fn_output_span = user_handler_fn.sig.output.span();
     let generated_location_fn_name = user_handler_fn_name.prepend(STEP_FN_LOCATION_FN_PREFIX);
     let generated_fn_name = user_handler_fn_name.prepend(STEP_FN_PREFIX);
     let generated_struct_name = user_handler_fn_name.prepend(STEP_STRUCT_PREFIX);
     let parameter_names = step.arguments.iter().map(|argument| &argument.cuke_runner_ident);
+    let parameter_types = step.arguments.iter()
+        .filter(|argument| !argument.scenario_arg)
+        .map(|argument| type_name(&argument.ty));
     let keyword = step.attribute.keyword;
     let expression = step.attribute.expression;
+    let compiled_expression = expression.value.compile(step.attribute.cucumber_expression)?;
+    check_step_arguments(&expression.value, &compiled_expression, &step.arguments)?;
+    let expression_type = compiled_expression.expression_type;
+    let timeout = Optional(step.attribute.timeout);
 
     let mut step_argument_index = 0;
     let mut data_statements = Vec::with_capacity(step.arguments.len());
@@ -115,6 +248,12 @@ fn codegen_step(step: Step) -> Result<TokenStream> {
         data_statements.push(data_statement);
     }
 
+    // spanned to the return type so that an unsupported return type
+    // is reported at the user handler function signature
+    let into_execution_result = quote_spanned! {user_handler_fn_output_span=>
+        ::cuke_runner::glue::error::IntoExecutionResult::into_execution_result(user_handler_fn_result)
+    };
+
     // quote_spanned so that the line information points
     // to the user handler function in the source file
     // instead of the macro invocation line
@@ -143,10 +282,9 @@ fn codegen_step(step: Step) -> Result<TokenStream> {
 
             #(#data_statements)*
 
-            // TODO: error handling...
             let result = ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(|| #user_handler_fn_name(#(#parameter_names),*)));
             match result {
-                Ok(user_handler_fn_result) => return Ok(()),
+                Ok(user_handler_fn_result) => return #into_execution_result,
                 Err(_err) => return Err(::cuke_runner::glue::error::ExecutionError::Panic(::cuke_runner::glue::error::PanicError::new())),
             };
         }
@@ -158,6 +296,9 @@ fn codegen_step(step: Step) -> Result<TokenStream> {
                 name: stringify!(#user_handler_fn_name),
                 keyword: #keyword,
                 expression: #expression,
+                expression_type: #expression_type,
+                parameter_types: &[#(#parameter_types),*],
+                timeout: #timeout,
                 step_fn: #generated_fn_name,
                 step_fn_location_fn: #generated_location_fn_name,
             };
@@ -210,6 +351,8 @@ fn incomplete_step(
             value: StepKeyword(keyword),
         },
         expression: keyword_attribute.expression,
+        timeout: keyword_attribute.timeout,
+        cucumber_expression: keyword_attribute.cucumber_expression,
     };
 
     codegen_step(parse_step(attribute, function)?)
@@ -227,3 +370,94 @@ pub fn step_attribute<K: Into<Option<crate::glue::step::StepKeyword>>>(
 
     result.unwrap_or_else(|diag| diag.emit_as_item_tokens())
 }
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn argument(name: &str, ty: syn::Type) -> GlueFnArg {
+        GlueFnArg {
+            scenario_arg: false,
+            user_ident: syn::Ident::new(name, Span::call_site()),
+            cuke_runner_ident: syn::Ident::new(name, Span::call_site()).prepend(crate::PARAM_PREFIX),
+            ty,
+        }
+    }
+
+    fn scenario_argument(name: &str, ty: syn::Type) -> GlueFnArg {
+        GlueFnArg {
+            scenario_arg: true,
+            ..argument(name, ty)
+        }
+    }
+
+    fn check(expression: &str, arguments: &[GlueFnArg]) -> bool {
+        let expression = StepExpression {
+            expression: expression.to_owned(),
+            span: Span::call_site(),
+        };
+        let compiled_expression = match expression.compile(None) {
+            Ok(compiled_expression) => compiled_expression,
+            Err(_) => panic!("invalid step expression \"{}\"", expression.expression),
+        };
+
+        check_step_arguments(&expression, &compiled_expression, arguments).is_ok()
+    }
+
+    #[test]
+    fn accepts_one_argument_per_capture() {
+        assert!(check("^I add (\\d+) and (\\d+)$", &[
+            scenario_argument("calc", parse_quote!(&mut Calc)),
+            argument("a", parse_quote!(u32)),
+            argument("b", parse_quote!(u32)),
+        ]));
+        assert!(check("I add {int} and {int}", &[
+            argument("a", parse_quote!(i32)),
+            argument("b", parse_quote!(i32)),
+        ]));
+    }
+
+    #[test]
+    fn accepts_a_trailing_doc_string_or_data_table_argument() {
+        assert!(check("^I enter (\\d+) rows$", &[
+            argument("count", parse_quote!(u32)),
+            argument("rows", parse_quote!(&DataTable)),
+        ]));
+        assert!(check("^I enter the text$", &[argument("text", parse_quote!(DocString))]));
+        assert!(check("^I enter the text$", &[argument("text", parse_quote!(&str))]));
+    }
+
+    #[test]
+    fn rejects_too_many_arguments() {
+        assert!(!check("^I press (\\d+)$", &[
+            argument("digit", parse_quote!(u32)),
+            argument("text", parse_quote!(&str)),
+            argument("rows", parse_quote!(&DataTable)),
+        ]));
+        assert!(!check("I press {int}", &[
+            argument("digit", parse_quote!(i32)),
+            argument("rows", parse_quote!(&DataTable)),
+            argument("text", parse_quote!(&str)),
+        ]));
+    }
+
+    #[test]
+    fn rejects_too_few_arguments() {
+        assert!(!check("^I add (\\d+) and (\\d+)$", &[argument("a", parse_quote!(u32))]));
+        assert!(!check("^I add (\\d+) and (\\d+)$", &[
+            argument("a", parse_quote!(u32)),
+            argument("rows", parse_quote!(&DataTable)),
+        ]));
+    }
+
+    #[test]
+    fn rejects_a_trailing_argument_that_is_no_doc_string_or_data_table() {
+        assert!(!check("^I press (\\d+)$", &[
+            argument("digit", parse_quote!(u32)),
+            argument("count", parse_quote!(u32)),
+        ]));
+        assert!(!check("^I press the button$", &[argument("button", parse_quote!(String))]));
+    }
+}
@@ -0,0 +1,71 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use proc_macro2::TokenStream;
+use devise::{Result, Diagnostic};
+use devise::ext::SpanDiagnosticExt;
+use syn::LitStr;
+use quote::quote;
+
+pub fn embed_features_macro(input: TokenStream) -> Result<TokenStream> {
+    let features_dir_lit = syn::parse2::<LitStr>(input)
+        .map_err(Diagnostic::from)?;
+    let span = features_dir_lit.span();
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR")
+        .map_err(|err| span.error(format!("could not read env variable \"CARGO_MANIFEST_DIR\": {}", err)))?;
+    let features_dir = Path::new(&manifest_dir).join(features_dir_lit.value());
+
+    let mut feature_file_paths = Vec::new();
+    find_feature_files(&features_dir, &mut feature_file_paths)
+        .map_err(|err| span.error(format!("could not read features directory \"{}\": {}",
+            features_dir.display(), err)))?;
+    feature_file_paths.sort();
+
+    let mut embedded_feature_files = Vec::with_capacity(feature_file_paths.len());
+    for feature_file_path in &feature_file_paths {
+        let uri = feature_file_path.strip_prefix(&features_dir).unwrap();
+        let uri_str = path_to_str(uri)
+            .map_err(|message| span.error(message))?;
+        let path_str = path_to_str(feature_file_path)
+            .map_err(|message| span.error(message))?;
+
+        // include_str! lets cargo rebuild the test binary when a feature file changes
+        embedded_feature_files.push(quote! {
+            ::cuke_runner::glue::EmbeddedFeatureFile {
+                uri: #uri_str,
+                source: include_str!(#path_str),
+            }
+        });
+    }
+
+    let embedded_features = quote! {
+        &[
+            #(#embedded_feature_files,
+            )*
+        ] as &'static [::cuke_runner::glue::EmbeddedFeatureFile]
+    };
+
+    Ok(embedded_features)
+}
+
+fn find_feature_files(dir: &Path, feature_file_paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            find_feature_files(&path, feature_file_paths)?;
+        } else if path.extension().map_or(false, |extension| extension == "feature") {
+            feature_file_paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn path_to_str(path: &Path) -> std::result::Result<&str, String> {
+    path.to_str().ok_or_else(|| format!("Path \"{}\" cannot be losslessly converted to an UTF-8 string \
+        and is thus currently not supported", path.display()))
+}
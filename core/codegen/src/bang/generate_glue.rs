@@ -3,34 +3,63 @@ use std::ops::Add;
 use std::path::PathBuf;
 
 use proc_macro2::{Span, TokenStream};
-use syn::{self, Path, Attribute, Item, Visibility};
+use syn::{self, Path, Attribute, Ident, Item, Lit, Meta, NestedMeta, Visibility};
 use devise::Result;
+use devise::ext::SpanDiagnosticExt;
 use quote::quote_spanned;
 
 use crate::{
+    BEFORE_ALL_HOOK_STRUCT_PREFIX,
+    BEFORE_FEATURE_HOOK_STRUCT_PREFIX,
     BEFORE_SCENARIO_HOOK_STRUCT_PREFIX,
     BEFORE_STEP_HOOK_STRUCT_PREFIX,
     STEP_STRUCT_PREFIX,
+    PARAMETER_TYPE_STRUCT_PREFIX,
     AFTER_STEP_HOOK_STRUCT_PREFIX,
     AFTER_SCENARIO_HOOK_STRUCT_PREFIX,
+    AFTER_FEATURE_HOOK_STRUCT_PREFIX,
+    AFTER_ALL_HOOK_STRUCT_PREFIX,
 };
 
 pub fn generate_glue_macro(_input: TokenStream) -> Result<TokenStream> {
     let current_file_path = super::get_current_file_path();
 
+    let step_expressions = parse_step_expressions(&current_file_path);
+    if let Some((_index, message)) = find_duplicate_step_expression(&step_expressions) {
+        return Err(Span::call_site().error(message));
+    }
+
+    let before_all_hook_definition_path_tokens: Vec<Path> = parse_paths(&current_file_path,
+        &["before_all"], BEFORE_ALL_HOOK_STRUCT_PREFIX);
+    let before_feature_hook_definition_path_tokens: Vec<Path> = parse_paths(&current_file_path,
+        &["before_feature"], BEFORE_FEATURE_HOOK_STRUCT_PREFIX);
     let before_scenario_hook_definition_path_tokens: Vec<Path> = parse_paths(&current_file_path,
         &["before_scenario"], BEFORE_SCENARIO_HOOK_STRUCT_PREFIX);
     let before_step_hook_definition_path_tokens: Vec<Path> = parse_paths(&current_file_path,
         &["before_step"], BEFORE_STEP_HOOK_STRUCT_PREFIX);
     let step_definition_path_tokens = parse_paths(&current_file_path,
-        &["given", "when", "then"], STEP_STRUCT_PREFIX);
+        STEP_ATTRIBUTE_NAMES, STEP_STRUCT_PREFIX);
+    let parameter_type_definition_path_tokens: Vec<Path> = parse_paths(&current_file_path,
+        &["parameter_type"], PARAMETER_TYPE_STRUCT_PREFIX);
     let after_step_hook_definition_path_tokens: Vec<Path> = parse_paths(&current_file_path,
         &["after_step"], AFTER_STEP_HOOK_STRUCT_PREFIX);
     let after_scenario_hook_definition_path_tokens: Vec<Path> = parse_paths(&current_file_path,
         &["after_scenario"], AFTER_SCENARIO_HOOK_STRUCT_PREFIX);
+    let after_feature_hook_definition_path_tokens: Vec<Path> = parse_paths(&current_file_path,
+        &["after_feature"], AFTER_FEATURE_HOOK_STRUCT_PREFIX);
+    let after_all_hook_definition_path_tokens: Vec<Path> = parse_paths(&current_file_path,
+        &["after_all"], AFTER_ALL_HOOK_STRUCT_PREFIX);
 
     let call_site_span = Span::call_site();
     let static_glue_definition_tokens = quote_spanned! {call_site_span=>
+        pub static BEFORE_ALL_HOOK_DEFINITIONS: &[&::cuke_runner::glue::hook::StaticGlobalHookDef] = &[
+            #(&#before_all_hook_definition_path_tokens,
+            )*
+        ];
+        pub static BEFORE_FEATURE_HOOK_DEFINITIONS: &[&::cuke_runner::glue::hook::StaticFeatureHookDef] = &[
+            #(&#before_feature_hook_definition_path_tokens,
+            )*
+        ];
         pub static BEFORE_SCENARIO_HOOK_DEFINITIONS: &[&::cuke_runner::glue::hook::StaticHookDef] = &[
             #(&#before_scenario_hook_definition_path_tokens,
             )*
@@ -43,6 +72,10 @@ pub fn generate_glue_macro(_input: TokenStream) -> Result<TokenStream> {
             #(&#step_definition_path_tokens,
             )*
         ];
+        pub static PARAMETER_TYPE_DEFINITIONS: &[&::cuke_runner::glue::step::parameter_type::StaticParameterTypeDef] = &[
+            #(&#parameter_type_definition_path_tokens,
+            )*
+        ];
         pub static AFTER_STEP_HOOK_DEFINITIONS: &[&::cuke_runner::glue::hook::StaticHookDef] = &[
             #(&#after_step_hook_definition_path_tokens,
             )*
@@ -51,25 +84,125 @@ pub fn generate_glue_macro(_input: TokenStream) -> Result<TokenStream> {
             #(&#after_scenario_hook_definition_path_tokens,
             )*
         ];
+        pub static AFTER_FEATURE_HOOK_DEFINITIONS: &[&::cuke_runner::glue::hook::StaticFeatureHookDef] = &[
+            #(&#after_feature_hook_definition_path_tokens,
+            )*
+        ];
+        pub static AFTER_ALL_HOOK_DEFINITIONS: &[&::cuke_runner::glue::hook::StaticGlobalHookDef] = &[
+            #(&#after_all_hook_definition_path_tokens,
+            )*
+        ];
     };
 
     Ok(TokenStream::from(static_glue_definition_tokens))
 }
 
-fn parse_paths(root_path: &PathBuf, fn_attribute_names: &[&str], struct_prefix: &str) -> Vec<Path> {
-    let mut static_glue_definition_paths = Vec::new();
+/// The attributes of step functions.
+const STEP_ATTRIBUTE_NAMES: &[&str] = &["step", "given", "when", "then"];
+
+/// A visible function, struct or enum of a glue module that is annotated with a glue attribute.
+struct GlueItem {
+    /// The path of the module of the item relative to the glue module, like `calculator::rpn`.
+    module_path: String,
+    name: String,
+    attribute: Attribute,
+}
+
+impl GlueItem {
+    /// Returns the path of the item relative to the glue module.
+    fn path(&self) -> String {
+        if self.module_path.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}::{}", self.module_path, self.name)
+        }
+    }
+}
+
+/// The step expression of a step function of a glue module.
+#[derive(Debug)]
+pub struct GlueStepExpression {
+    pub expression: String,
+    /// The path of the step function relative to the glue module.
+    pub function_path: String,
+}
+
+/// Returns the step expressions of the step functions in the glue module file and its submodules.
+pub fn parse_step_expressions(root_path: &PathBuf) -> Vec<GlueStepExpression> {
+    parse_glue_items(root_path, STEP_ATTRIBUTE_NAMES).into_iter()
+        .filter_map(|glue_item| step_expression(&glue_item.attribute).map(|expression| GlueStepExpression {
+            expression,
+            function_path: glue_item.path(),
+        }))
+        .collect()
+}
+
+/// Returns the index of the first step expression that was already declared by a previous step function,
+/// together with an error message that names both step functions.
+pub fn find_duplicate_step_expression(step_expressions: &[GlueStepExpression]) -> Option<(usize, String)> {
+    for (index, second) in step_expressions.iter().enumerate() {
+        let first = step_expressions[..index].iter()
+            .find(|first| first.expression == second.expression);
+
+        if let Some(first) = first {
+            return Some((index, format!("duplicate step expression \"{}\":
+   first: {}
+  second: {}", second.expression, first.function_path, second.function_path)));
+        }
+    }
+
+    None
+}
+
+/// Returns the expression of a step attribute like `#[given("^I have (\\d+) cukes$")]`.
+fn step_expression(attribute: &Attribute) -> Option<String> {
+    let meta_list = match attribute.parse_meta() {
+        Ok(Meta::List(meta_list)) => meta_list,
+        _ => return None,
+    };
+
+    meta_list.nested.iter().find_map(|nested_meta| match nested_meta {
+        NestedMeta::Lit(Lit::Str(lit)) => Some(lit.value()),
+        NestedMeta::Meta(Meta::NameValue(name_value)) if name_value.path.is_ident("expression") => {
+            match name_value.lit {
+                Lit::Str(ref lit) => Some(lit.value()),
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+fn parse_paths(root_path: &PathBuf, attribute_names: &[&str], struct_prefix: &str) -> Vec<Path> {
+    parse_glue_items(root_path, attribute_names).into_iter()
+        .map(|glue_item| {
+            let item_path = String::new()
+                .add("crate")
+                .add("::")
+                .add(&glue_item.module_path)
+                .add("::")
+                .add(struct_prefix)
+                .add(&glue_item.name);
+
+            syn::parse_str::<Path>(&item_path)
+                .expect("parse glue definition path")
+        })
+        .collect()
+}
+
+fn parse_glue_items(root_path: &PathBuf, attribute_names: &[&str]) -> Vec<GlueItem> {
+    let mut glue_items = Vec::new();
 
     let mut module_paths = Vec::new();
 
-    add_from_file(&mut static_glue_definition_paths, &mut module_paths, &mut root_path.clone(),
-        fn_attribute_names, struct_prefix);
+    add_from_file(&mut glue_items, &mut module_paths, &mut root_path.clone(), attribute_names);
 
-    static_glue_definition_paths
+    glue_items
 }
 
-fn add_from_file(static_glue_definition_paths: &mut Vec<Path>,
+fn add_from_file(glue_items: &mut Vec<GlueItem>,
     module_paths: &mut Vec<String>, fs_path: &mut PathBuf,
-    fn_attribute_names: &[&str], struct_prefix: &str) {
+    attribute_names: &[&str]) {
 
     log::debug!("Searching for glue definitions in: {}", &fs_path.display());
 
@@ -84,43 +217,26 @@ fn add_from_file(static_glue_definition_paths: &mut Vec<Path>,
 
     fs_path.pop();
 
-    add_from_items(static_glue_definition_paths, module_paths, fs_path, syntax.items,
-        fn_attribute_names, struct_prefix);
+    add_from_items(glue_items, module_paths, fs_path, syntax.items, attribute_names);
 }
 
-fn add_from_items(static_glue_definition_paths: &mut Vec<Path>,
+fn add_from_items(glue_items: &mut Vec<GlueItem>,
     module_paths: &mut Vec<String>, fs_path: &mut PathBuf, items: Vec<Item>,
-    fn_attribute_names: &[&str], struct_prefix: &str) {
+    attribute_names: &[&str]) {
 
     for item in items {
         match item {
             Item::Fn(item_fn) => {
-                let current_path = module_paths.join("::");
-                let function_name = item_fn.sig.ident.to_string();
-                let glue_fn_path = format!("{}::{}", current_path, &function_name);
-
-                if !is_visible(item_fn.vis) {
-                    log::debug!("Skipping private function: {}", glue_fn_path);
-                    continue;
-                }
-
-                if get_attribute(item_fn.attrs, fn_attribute_names).is_none() {
-                    log::debug!("Skipping function without glue attribute: {}", glue_fn_path);
-                    continue;
-                }
-
-                let function_path = String::new()
-                    .add("crate")
-                    .add("::")
-                    .add(&current_path)
-                    .add("::")
-                    .add(struct_prefix)
-                    .add(&function_name);
-
-                let path = syn::parse_str::<Path>(&function_path)
-                    .expect("parse glue definition path");
-
-                static_glue_definition_paths.push(path);
+                add_from_item(glue_items, module_paths, item_fn.vis, &item_fn.sig.ident,
+                    item_fn.attrs, attribute_names);
+            },
+            Item::Struct(item_struct) => {
+                add_from_item(glue_items, module_paths, item_struct.vis, &item_struct.ident,
+                    item_struct.attrs, attribute_names);
+            },
+            Item::Enum(item_enum) => {
+                add_from_item(glue_items, module_paths, item_enum.vis, &item_enum.ident,
+                    item_enum.attrs, attribute_names);
             },
             Item::Mod(item_mod) => {
                 let module_name = item_mod.ident.to_string();
@@ -141,15 +257,13 @@ fn add_from_items(static_glue_definition_paths: &mut Vec<Path>,
                     let module_items = content.1;
 
                     module_paths.push(module_name);
-                    add_from_items(static_glue_definition_paths, module_paths, fs_path, module_items,
-                        fn_attribute_names, struct_prefix);
+                    add_from_items(glue_items, module_paths, fs_path, module_items, attribute_names);
                     module_paths.pop();
                 } else if let Some(_semi) = item_mod.semi {
                     fs_path.push(format!("{}.rs", &module_name));
                     if fs_path.exists() {
                         module_paths.push(module_name);
-                        add_from_file(static_glue_definition_paths, module_paths, fs_path,
-                            fn_attribute_names, struct_prefix);
+                        add_from_file(glue_items, module_paths, fs_path, attribute_names);
                         module_paths.pop();
                         continue;
                     }
@@ -159,8 +273,7 @@ fn add_from_items(static_glue_definition_paths: &mut Vec<Path>,
                     fs_path.push("mod.rs");
                     if fs_path.exists() {
                         module_paths.push(module_name);
-                        add_from_file(static_glue_definition_paths, module_paths, fs_path,
-                            fn_attribute_names, struct_prefix);
+                        add_from_file(glue_items, module_paths, fs_path, attribute_names);
                         module_paths.pop();
 
                         fs_path.pop();
@@ -177,6 +290,33 @@ fn add_from_items(static_glue_definition_paths: &mut Vec<Path>,
     }
 }
 
+/// Adds a function, struct or enum if it is annotated with one of the glue attributes.
+fn add_from_item(glue_items: &mut Vec<GlueItem>, module_paths: &[String],
+    visibility: Visibility, ident: &Ident, attrs: Vec<Attribute>,
+    attribute_names: &[&str]) {
+
+    let glue_item_path = format!("{}::{}", module_paths.join("::"), ident);
+
+    let attribute = match get_attribute(attrs, attribute_names) {
+        Some(attribute) => attribute,
+        None => {
+            log::debug!("Skipping item without glue attribute: {}", glue_item_path);
+            return;
+        }
+    };
+
+    if !is_visible(visibility) {
+        log::debug!("Skipping private item: {}", glue_item_path);
+        return;
+    }
+
+    glue_items.push(GlueItem {
+        module_path: module_paths.join("::"),
+        name: ident.to_string(),
+        attribute,
+    });
+}
+
 fn is_visible(visibility: Visibility) -> bool {
     match visibility {
         Visibility::Public(_) => true,
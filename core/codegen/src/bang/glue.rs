@@ -1,8 +1,11 @@
 use proc_macro2::TokenStream;
 use devise::{Result, Spanned, Diagnostic};
+use devise::ext::SpanDiagnosticExt;
 use syn::{parse::Parser, Path, punctuated::Punctuated, token::Comma};
 use quote::{quote, quote_spanned};
 
+use super::generate_glue::{find_duplicate_step_expression, parse_step_expressions, GlueStepExpression};
+
 pub fn glue_macro(input: TokenStream) -> Result<TokenStream> {
     let current_file_path = super::get_current_file_path();
     let base_path = current_file_path.parent().unwrap();
@@ -16,14 +19,21 @@ pub fn glue_macro(input: TokenStream) -> Result<TokenStream> {
         .parse2(input)
         .map_err(Diagnostic::from)?;
 
+    check_duplicate_step_expressions(base_path, &paths)?;
+
     let static_glue_definitions = paths.into_iter()
         .map(|path| quote_spanned! {path.span().into()=>
             ::cuke_runner::glue::StaticGlueDefinitions {
+                before_all_hooks: #path::BEFORE_ALL_HOOK_DEFINITIONS,
+                before_feature_hooks: #path::BEFORE_FEATURE_HOOK_DEFINITIONS,
                 before_scenario_hooks: #path::BEFORE_SCENARIO_HOOK_DEFINITIONS,
                 before_step_hooks: #path::BEFORE_STEP_HOOK_DEFINITIONS,
                 steps: #path::STEP_DEFINITIONS,
+                parameter_types: #path::PARAMETER_TYPE_DEFINITIONS,
                 after_step_hooks: #path::AFTER_STEP_HOOK_DEFINITIONS,
                 after_scenario_hooks: #path::AFTER_SCENARIO_HOOK_DEFINITIONS,
+                after_feature_hooks: #path::AFTER_FEATURE_HOOK_DEFINITIONS,
+                after_all_hooks: #path::AFTER_ALL_HOOK_DEFINITIONS,
             }
         });
 
@@ -42,6 +52,67 @@ pub fn glue_macro(input: TokenStream) -> Result<TokenStream> {
     Ok(TokenStream::from(glue))
 }
 
+/// Rejects step expressions that are declared by step functions of different glue modules.
+///
+/// Step expressions within one glue module were already checked by its `generate_glue!` invocation.
+fn check_duplicate_step_expressions(base_path: &std::path::Path, paths: &Punctuated<Path, Comma>)
+    -> Result<()>
+{
+    let mut step_expressions = Vec::new();
+    let mut step_expression_paths = Vec::new();
+
+    for path in paths {
+        // modules that cannot be found in the file system (e.g. `crate::steps`)
+        // are still checked at runtime when the glue is created
+        let module_file_path = match module_file_path(base_path, path) {
+            Some(module_file_path) => module_file_path,
+            None => continue,
+        };
+
+        let module = path.segments.iter()
+            .map(|segment| segment.ident.to_string())
+            .collect::<Vec<String>>()
+            .join("::");
+        for step_expression in parse_step_expressions(&module_file_path) {
+            step_expressions.push(GlueStepExpression {
+                expression: step_expression.expression,
+                function_path: format!("{}::{}", module, step_expression.function_path),
+            });
+            step_expression_paths.push(path);
+        }
+    }
+
+    match find_duplicate_step_expression(&step_expressions) {
+        Some((index, message)) => Err(step_expression_paths[index].span().error(message)),
+        None => Ok(()),
+    }
+}
+
+/// Returns the source file of a glue module path like `steps` or `steps::calculator`
+/// relative to the directory of the file that invokes `glue!`.
+fn module_file_path(base_path: &std::path::Path, path: &Path) -> Option<std::path::PathBuf> {
+    if path.leading_colon.is_some() {
+        return None;
+    }
+
+    let mut fs_path = base_path.to_path_buf();
+    for segment in &path.segments {
+        fs_path.push(segment.ident.to_string());
+    }
+
+    let file_path = fs_path.with_extension("rs");
+    if file_path.is_file() {
+        return Some(file_path);
+    }
+
+    let mod_file_path = fs_path.join("mod.rs");
+    if mod_file_path.is_file() {
+        return Some(mod_file_path);
+    }
+
+    None
+}
+
 fn path_to_str(path: &std::path::Path) -> &str {
     match path.to_str() {
         Some(path_str) => path_str,
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use proc_macro2::TokenStream;
 use quote::quote;
 
+mod embed_features;
 mod generate_glue;
 mod glue;
 
@@ -19,6 +20,12 @@ pub fn glue_macro(input: proc_macro::TokenStream) -> TokenStream {
         .unwrap_or_else(|_| quote!(()).into())
 }
 
+pub fn embed_features_macro(input: proc_macro::TokenStream) -> TokenStream {
+    embed_features::embed_features_macro(input.into())
+        .map_err(|diag| diag.emit_as_expr_tokens())
+        .unwrap_or_else(|_| quote!(()).into())
+}
+
 fn get_current_file_path() -> PathBuf {
     let crate_root_path = env::current_dir()
         .expect("current directory for crate root path");
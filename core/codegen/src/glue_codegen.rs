@@ -1,5 +1,5 @@
 use quote::ToTokens;
-use proc_macro2::TokenStream as TokenStream2;
+use proc_macro2::{Span, TokenStream as TokenStream2};
 use devise::{FromMeta, MetaItem, Result};
 use devise::ext::SpanDiagnosticExt;
 use quote::quote;
@@ -14,12 +14,49 @@ pub struct HookType(pub glue::hook::HookType);
 #[derive(Debug)]
 pub struct StepKeyword(pub glue::step::StepKeyword);
 
+/// The raw step expression string of a step attribute.
 #[derive(Debug)]
-pub struct Regex(pub regex::Regex);
+pub struct StepExpression {
+    pub expression: String,
+    /// The span of the expression without the quotes.
+    pub span: Span,
+}
+
+/// A step expression that was validated and compiled to a regex.
+#[derive(Debug)]
+pub struct CompiledStepExpression {
+    pub expression_type: ExpressionType,
+    pub regex: regex::Regex,
+    /// The parameter types of a cucumber expression, `None` for regexes.
+    pub parameter_types: Option<Vec<glue::step::cucumber_expression::ParameterType>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ExpressionType(pub glue::step::ExpressionType);
+
+/// The name of a user-defined parameter type like `color`.
+#[derive(Debug)]
+pub struct ParameterTypeName(pub String);
+
+/// The regex of a user-defined parameter type like `red|green|blue`.
+#[derive(Debug)]
+pub struct ParameterTypeRegex(pub String);
+
+/// Stands in for the user-defined parameter types of cucumber expressions,
+/// which are only known when the glue is collected.
+static USER_DEFINED_PARAMETER_TYPE: glue::step::parameter_type::StaticParameterTypeDef =
+    glue::step::parameter_type::StaticParameterTypeDef {
+        name: "",
+        regex: ".*",
+        type_name: "",
+    };
 
 #[derive(Debug)]
 pub struct TagExpression(pub String);
 
+#[derive(Debug)]
+pub struct Timeout(pub std::time::Duration);
+
 #[derive(Clone, Debug)]
 pub struct Optional<T>(pub Option<T>);
 
@@ -29,13 +66,18 @@ impl FromMeta for StringLit {
     }
 }
 
-const VALID_HOOK_TYPES_STR: &str = "`BeforeScenario`, `BeforeStep`, `AfterStep`, `AfterScenario`";
+const VALID_HOOK_TYPES_STR: &str = "`BeforeAll`, `BeforeFeature`, `BeforeScenario`, `BeforeStep`, \
+    `AfterStep`, `AfterScenario`, `AfterFeature`, `AfterAll`";
 
 const VALID_HOOK_TYPES: &[glue::hook::HookType] = &[
+    glue::hook::HookType::BeforeAll,
+    glue::hook::HookType::BeforeFeature,
     glue::hook::HookType::BeforeScenario,
     glue::hook::HookType::BeforeStep,
     glue::hook::HookType::AfterStep,
     glue::hook::HookType::AfterScenario,
+    glue::hook::HookType::AfterFeature,
+    glue::hook::HookType::AfterAll,
 ];
 
 impl FromMeta for HookType {
@@ -64,10 +106,14 @@ impl ToTokens for HookType {
         use crate::glue::hook::HookType::*;
 
         let keyword_tokens = match self.0 {
+            BeforeAll => quote!(::cuke_runner::glue::HookType::BeforeAll),
+            BeforeFeature => quote!(::cuke_runner::glue::HookType::BeforeFeature),
             BeforeScenario => quote!(::cuke_runner::glue::HookType::BeforeScenario),
             BeforeStep => quote!(::cuke_runner::glue::HookType::BeforeStep),
             AfterStep => quote!(::cuke_runner::glue::HookType::AfterStep),
             AfterScenario => quote!(::cuke_runner::glue::HookType::AfterScenario),
+            AfterFeature => quote!(::cuke_runner::glue::HookType::AfterFeature),
+            AfterAll => quote!(::cuke_runner::glue::HookType::AfterAll),
         };
 
         tokens.extend(keyword_tokens);
@@ -119,20 +165,131 @@ impl ToTokens for StepKeyword {
     }
 }
 
-impl FromMeta for Regex {
+impl FromMeta for StepExpression {
+    fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
+        let string = StringLit::from_meta(meta)?;
+
+        Ok(StepExpression {
+            span: string.subspan(1..=string.len()),
+            expression: string.0,
+        })
+    }
+}
+
+impl StepExpression {
+    /// Compiles the expression as cucumber expression or as regex.
+    ///
+    /// Whether it is a cucumber expression is detected from the expression
+    /// unless it is explicitly declared with `cucumber_expression = true/false`.
+    pub fn compile(&self, cucumber_expression: Option<bool>) -> Result<CompiledStepExpression> {
+        use crate::glue::step::cucumber_expression::{is_cucumber_expression, CucumberExpression, ParameterType};
+        use crate::glue::step::parameter_type::is_valid_name;
+
+        let expression = &*self.expression;
+        let is_cucumber_expression = cucumber_expression
+            .unwrap_or_else(|| is_cucumber_expression(expression));
+
+        if is_cucumber_expression {
+            // user-defined parameter types are validated when the glue is collected
+            let cucumber_expression = CucumberExpression::parse_with(expression, |name| {
+                ParameterType::from_name(name).or_else(|| if is_valid_name(name) {
+                    Some(ParameterType::Custom(&USER_DEFINED_PARAMETER_TYPE))
+                } else {
+                    None
+                })
+            })
+                .map_err(|err| self.span.error(format!(
+                    "step expression \"{}\" is not a valid cucumber expression: {}", expression, err)))?;
+            let regex = regex::Regex::new(cucumber_expression.regex())
+                .map_err(|err| self.span.error(format!(
+                    "step expression \"{}\" is not a valid cucumber expression: {}", expression, err)))?;
+
+            Ok(CompiledStepExpression {
+                expression_type: ExpressionType(glue::step::ExpressionType::CucumberExpression),
+                regex,
+                parameter_types: Some(cucumber_expression.parameter_types().to_vec()),
+            })
+        } else {
+            let regex = regex::Regex::new(expression)
+                .map_err(|err| self.span.error(format!(
+                    "step expression \"{}\" is not a valid regex: {}", expression, err)))?;
+
+            Ok(CompiledStepExpression {
+                expression_type: ExpressionType(glue::step::ExpressionType::Regex),
+                regex,
+                parameter_types: None,
+            })
+        }
+    }
+}
+
+impl ToTokens for StepExpression {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let string = self.expression.as_str();
+        tokens.extend(quote!(#string));
+    }
+}
+
+impl ToTokens for ExpressionType {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        use crate::glue::step::ExpressionType::*;
+
+        let expression_type_tokens = match self.0 {
+            Regex => quote!(::cuke_runner::glue::step::ExpressionType::Regex),
+            CucumberExpression => quote!(::cuke_runner::glue::step::ExpressionType::CucumberExpression),
+        };
+
+        tokens.extend(expression_type_tokens);
+    }
+}
+
+impl FromMeta for ParameterTypeName {
     fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
+        use crate::glue::step::cucumber_expression::ParameterType;
+        use crate::glue::step::parameter_type::is_valid_name;
+
         let string = StringLit::from_meta(meta)?;
         let span = string.subspan(1..=string.len());
 
-        let result = regex::Regex::new(&string);
-        match result {
-            Ok(regex) => Ok(Regex(regex)),
-            Err(err) => Err(span.error(format!("step expression \"{}\" is not a valid regex: {}", &*string, err))),
+        if ParameterType::from_name(&string).is_some() {
+            return Err(span.error(format!("parameter type name \"{}\" is reserved for \
+                the built-in parameter type {{{}}}", &*string, &*string)));
         }
+        if !is_valid_name(&string) {
+            return Err(span.error(format!("parameter type name \"{}\" is invalid", &*string))
+                .help("names start with a letter or an underscore and only contain \
+                    alphanumeric chars or underscores"));
+        }
+
+        Ok(ParameterTypeName(string.0))
     }
 }
 
-impl ToTokens for Regex {
+impl ToTokens for ParameterTypeName {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let string = self.0.as_str();
+        tokens.extend(quote!(#string));
+    }
+}
+
+impl FromMeta for ParameterTypeRegex {
+    fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
+        let string = StringLit::from_meta(meta)?;
+        let span = string.subspan(1..=string.len());
+
+        let regex = regex::Regex::new(&string)
+            .map_err(|err| span.error(format!("parameter type regex \"{}\" is invalid: {}", &*string, err)))?;
+        // the first capture group is the implicit group of the whole match
+        if regex.captures_len() > 1 {
+            return Err(span.error(format!("parameter type regex \"{}\" must not contain capture groups",
+                &*string)).help("use non-capturing groups like `(?:...)` instead"));
+        }
+
+        Ok(ParameterTypeRegex(string.0))
+    }
+}
+
+impl ToTokens for ParameterTypeRegex {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let string = self.0.as_str();
         tokens.extend(quote!(#string));
@@ -159,6 +316,27 @@ impl ToTokens for TagExpression {
     }
 }
 
+impl FromMeta for Timeout {
+    fn from_meta(meta: MetaItem<'_>) -> Result<Self> {
+        let string = StringLit::from_meta(meta)?;
+        let span = string.subspan(1..=string.len());
+
+        let result = glue::timeout::parse_timeout(string.as_ref());
+        match result {
+            Ok(duration) => Ok(Timeout(duration)),
+            Err(err) => Err(span.error(err)),
+        }
+    }
+}
+
+impl ToTokens for Timeout {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let secs = self.0.as_secs();
+        let nanos = self.0.subsec_nanos();
+        tokens.extend(quote!(::std::time::Duration::new(#secs, #nanos)));
+    }
+}
+
 impl<T: ToTokens> ToTokens for Optional<T> {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let opt_tokens = match self.0 {
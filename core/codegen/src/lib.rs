@@ -16,6 +16,12 @@ mod bang;
 mod glue_codegen;
 mod syn_ext;
 
+static BEFORE_ALL_HOOK_STRUCT_PREFIX: &str = "static_cuke_runner_before_all_hook_info_for_";
+static BEFORE_ALL_HOOK_FN_LOCATION_FN_PREFIX: &str = "cuke_runner_before_all_hook_fn_location_fn_";
+static BEFORE_ALL_HOOK_FN_PREFIX: &str = "cuke_runner_before_all_hook_fn_";
+static BEFORE_FEATURE_HOOK_STRUCT_PREFIX: &str = "static_cuke_runner_before_feature_hook_info_for_";
+static BEFORE_FEATURE_HOOK_FN_LOCATION_FN_PREFIX: &str = "cuke_runner_before_feature_hook_fn_location_fn_";
+static BEFORE_FEATURE_HOOK_FN_PREFIX: &str = "cuke_runner_before_feature_hook_fn_";
 static BEFORE_SCENARIO_HOOK_STRUCT_PREFIX: &str = "static_cuke_runner_before_scenario_hook_info_for_";
 static BEFORE_SCENARIO_HOOK_FN_LOCATION_FN_PREFIX: &str = "cuke_runner_before_scenario_hook_fn_location_fn_";
 static BEFORE_SCENARIO_HOOK_FN_PREFIX: &str = "cuke_runner_before_scenario_hook_fn_";
@@ -28,11 +34,20 @@ static AFTER_STEP_HOOK_FN_PREFIX: &str = "cuke_runner_after_step_hook_fn_";
 static AFTER_SCENARIO_HOOK_STRUCT_PREFIX: &str = "static_cuke_runner_after_scenario_hook_info_for_";
 static AFTER_SCENARIO_HOOK_FN_LOCATION_FN_PREFIX: &str = "cuke_runner_after_scenario_hook_fn_location_fn_";
 static AFTER_SCENARIO_HOOK_FN_PREFIX: &str = "cuke_runner_after_scenario_hook_fn_";
+static AFTER_FEATURE_HOOK_STRUCT_PREFIX: &str = "static_cuke_runner_after_feature_hook_info_for_";
+static AFTER_FEATURE_HOOK_FN_LOCATION_FN_PREFIX: &str = "cuke_runner_after_feature_hook_fn_location_fn_";
+static AFTER_FEATURE_HOOK_FN_PREFIX: &str = "cuke_runner_after_feature_hook_fn_";
+static AFTER_ALL_HOOK_STRUCT_PREFIX: &str = "static_cuke_runner_after_all_hook_info_for_";
+static AFTER_ALL_HOOK_FN_LOCATION_FN_PREFIX: &str = "cuke_runner_after_all_hook_fn_location_fn_";
+static AFTER_ALL_HOOK_FN_PREFIX: &str = "cuke_runner_after_all_hook_fn_";
 
 static STEP_STRUCT_PREFIX: &str = "static_cuke_runner_step_info_for_";
 static STEP_FN_LOCATION_FN_PREFIX: &str = "cuke_runner_step_fn_location_fn_";
 static STEP_FN_PREFIX: &str = "cuke_runner_step_fn_";
 
+static PARAMETER_TYPE_STRUCT_PREFIX: &str = "static_cuke_runner_parameter_type_info_for_";
+static PARAMETER_TYPE_ASSERT_FN_PREFIX: &str = "cuke_runner_parameter_type_assert_fn_";
+
 static PARAM_PREFIX: &str = "__cuke_runner_param_";
 
 macro_rules! emit {
@@ -65,10 +80,14 @@ macro_rules! hook_attribute {
 }
 
 hook_attribute!(hook => None);
+hook_attribute!(before_all => HookType::BeforeAll);
+hook_attribute!(before_feature => HookType::BeforeFeature);
 hook_attribute!(before_scenario => HookType::BeforeScenario);
 hook_attribute!(before_step => HookType::BeforeStep);
 hook_attribute!(after_step => HookType::AfterStep);
 hook_attribute!(after_scenario => HookType::AfterScenario);
+hook_attribute!(after_feature => HookType::AfterFeature);
+hook_attribute!(after_all => HookType::AfterAll);
 
 macro_rules! step_attribute {
     ($name:ident => $keyword:expr) => (
@@ -84,6 +103,16 @@ step_attribute!(given => StepKeyword::Given);
 step_attribute!(when => StepKeyword::When);
 step_attribute!(then => StepKeyword::Then);
 
+/// Declares a user-defined parameter type for a `FromStr` type, e.g.
+/// `#[parameter_type(name = "color", regex = "red|green|blue")]`.
+///
+/// Step expressions reference it as `{color}` in cucumber expressions
+/// or as `(?P<color>)` placeholder in regexes.
+#[proc_macro_attribute]
+pub fn parameter_type(args: TokenStream, input: TokenStream) -> TokenStream {
+    emit!(attribute::parameter_type::parameter_type_attribute(args, input))
+}
+
 
 #[proc_macro]
 pub fn generate_glue(input: TokenStream) -> TokenStream {
@@ -94,3 +123,11 @@ pub fn generate_glue(input: TokenStream) -> TokenStream {
 pub fn glue(input: TokenStream) -> TokenStream {
     emit!(bang::glue_macro(input))
 }
+
+/// Embeds the feature files of a directory (relative to the crate root) into the test binary,
+/// so that `Config::embedded_features` can run them without the source tree, e.g.
+/// `embedded_features: Some(cuke_runner::embed_features!("tests/features"))`.
+#[proc_macro]
+pub fn embed_features(input: TokenStream) -> TokenStream {
+    emit!(bang::embed_features_macro(input))
+}
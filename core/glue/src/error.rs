@@ -1,3 +1,4 @@
+use std::error::Error as StdError;
 use std::fmt;
 
 use failure::Fail;
@@ -64,3 +65,143 @@ impl fmt::Display for ExecutionError {
         }
     }
 }
+
+/// Converts the return value of a step or hook function into an execution result.
+///
+/// This is implemented for `()` and for `Result<(), E>` with any `E: std::error::Error`,
+/// so step and hook functions can use the `?` operator instead of unwrapping.
+pub trait IntoExecutionResult {
+    fn into_execution_result(self) -> ::std::result::Result<(), ExecutionError>;
+}
+
+impl IntoExecutionResult for () {
+    fn into_execution_result(self) -> ::std::result::Result<(), ExecutionError> {
+        Ok(())
+    }
+}
+
+impl<E: StdError + Send + Sync + 'static> IntoExecutionResult for ::std::result::Result<(), E> {
+    fn into_execution_result(self) -> ::std::result::Result<(), ExecutionError> {
+        self.map_err(|error| ExecutionError::Other(::failure::Error::from(GlueFnError::new(error))))
+    }
+}
+
+/// An error that was returned by a step or hook function.
+///
+/// The sources of the returned error are available as causes of this error.
+#[derive(Debug)]
+pub struct GlueFnError {
+    error: Box<dyn StdError + Send + Sync>,
+    source: Option<Box<ErrorSource>>,
+}
+
+impl GlueFnError {
+    #[doc(hidden)]
+    pub fn new<E: StdError + Send + Sync + 'static>(error: E) -> GlueFnError {
+        let source = ErrorSource::chain(error.source());
+
+        GlueFnError {
+            error: Box::new(error),
+            source,
+        }
+    }
+
+    /// Returns the error that was returned by the step or hook function.
+    pub fn get_ref(&self) -> &(dyn StdError + Send + Sync + 'static) {
+        &*self.error
+    }
+}
+
+impl Fail for GlueFnError {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.source.as_ref().map(|source| &**source as &dyn Fail)
+    }
+}
+
+impl fmt::Display for GlueFnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+/// The message of a `std::error::Error` source, which cannot be used as `Fail` cause directly.
+#[derive(Debug)]
+struct ErrorSource {
+    message: String,
+    source: Option<Box<ErrorSource>>,
+}
+
+impl ErrorSource {
+    fn chain(error: Option<&(dyn StdError + 'static)>) -> Option<Box<ErrorSource>> {
+        error.map(|error| Box::new(ErrorSource {
+            message: error.to_string(),
+            source: ErrorSource::chain(error.source()),
+        }))
+    }
+}
+
+impl Fail for ErrorSource {
+    fn cause(&self) -> Option<&dyn Fail> {
+        self.source.as_ref().map(|source| &**source as &dyn Fail)
+    }
+}
+
+impl fmt::Display for ErrorSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.message.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct ConnectionError {
+        source: io::Error,
+    }
+
+    impl fmt::Display for ConnectionError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "could not connect to database")
+        }
+    }
+
+    impl StdError for ConnectionError {
+        fn source(&self) -> Option<&(dyn StdError + 'static)> {
+            Some(&self.source)
+        }
+    }
+
+    #[test]
+    fn unit_is_ok() {
+        assert!(().into_execution_result().is_ok());
+    }
+
+    #[test]
+    fn ok_result_is_ok() {
+        let result: ::std::result::Result<(), io::Error> = Ok(());
+
+        assert!(result.into_execution_result().is_ok());
+    }
+
+    #[test]
+    fn err_result_keeps_error_sources_as_causes() {
+        let result: ::std::result::Result<(), ConnectionError> = Err(ConnectionError {
+            source: io::Error::new(io::ErrorKind::ConnectionRefused, "connection refused"),
+        });
+
+        let error = match result.into_execution_result() {
+            Err(ExecutionError::Other(error)) => error,
+            other => panic!("expected ExecutionError::Other, got: {:?}", other),
+        };
+
+        let messages = error.iter_chain()
+            .map(|fail| fail.to_string())
+            .collect::<Vec<String>>();
+        assert_eq!(messages, vec!["could not connect to database", "connection refused"]);
+        assert!(error.downcast_ref::<GlueFnError>().unwrap().get_ref().is::<ConnectionError>());
+    }
+}
@@ -0,0 +1,50 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::global::Global;
+
+/// Data that is shared by all scenarios of a feature file.
+///
+/// It is filled by `#[before_feature]` hooks, can be read by every scenario of the feature
+/// through [`Scenario::feature`] and is handed to the `#[after_feature]` hooks for cleanup.
+///
+/// Like the [`Global`] data, the feature data may be shared between threads
+/// and thus only accepts `Send + Sync` values.
+///
+/// [`Scenario::feature`]: ../scenario/struct.Scenario.html#method.feature
+/// [`Global`]: ../global/struct.Global.html
+#[derive(Debug, Default)]
+pub struct Feature {
+    data: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    global: Arc<Global>,
+}
+
+impl Feature {
+    #[doc(hidden)]
+    pub fn new(global: Arc<Global>) -> Feature {
+        Feature {
+            data: HashMap::new(),
+            global,
+        }
+    }
+
+    /// Returns the data that is shared by all scenarios of the test run.
+    pub fn global(&self) -> &Global {
+        &self.global
+    }
+
+    pub fn set<T: Send + Sync + 'static>(&mut self, data: T) {
+        self.data.insert(TypeId::of::<T>(), Box::new(data));
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.data.get(&TypeId::of::<T>())
+            .map(|value| value.downcast_ref::<T>().unwrap())
+    }
+
+    pub fn get_mut<T: Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        self.data.get_mut(&TypeId::of::<T>())
+            .map(|value| value.downcast_mut::<T>().unwrap())
+    }
+}
@@ -13,6 +13,33 @@ impl<'p> LinePredicate<'p> {
         }
     }
 
+    /// Parses selectors like `path/file.feature:8` or `path/file.feature:4:8`.
+    pub fn parse(selectors: &[&'p str]) -> Result<LinePredicate<'p>, String> {
+        let mut line_filters: HashMap<&'p str, Vec<u32>> = HashMap::with_capacity(selectors.len());
+
+        for selector in selectors {
+            let mut parts = selector.split(':');
+            let uri = parts.next().unwrap_or_default();
+            let lines = parts
+                .map(|line| line.parse::<u32>()
+                    .map_err(|_| format!("line selector \"{}\" has an invalid line \"{}\"", selector, line)))
+                .collect::<Result<Vec<u32>, String>>()?;
+
+            if uri.is_empty() || lines.is_empty() {
+                return Err(format!("line selector \"{}\" must look like \"path/file.feature:line\"", selector));
+            }
+
+            line_filters.entry(uri).or_insert_with(Vec::new).extend(lines);
+        }
+
+        Ok(LinePredicate::new(line_filters))
+    }
+
+    /// The uris of all feature files that have line filters.
+    pub fn uris(&self) -> impl Iterator<Item = &'p str> + '_ {
+        self.line_filters.keys().copied()
+    }
+
     pub fn apply(&self, uri: &str, locations: &[Location]) -> bool {
         match self.line_filters.get(uri) {
             Some(lines) => {
@@ -66,6 +93,23 @@ mod tests {
         assert!(!predicate.apply("path/file.feature", &[location(4), location(8)]));
     }
 
+    #[test]
+    pub fn parses_selectors_with_several_lines() {
+        let predicate = LinePredicate::parse(&["path/file.feature:4:8", "path/file.feature:12"]).unwrap();
+
+        assert!(predicate.apply("path/file.feature", &[location(8)]));
+        assert!(predicate.apply("path/file.feature", &[location(12)]));
+        assert!(!predicate.apply("path/file.feature", &[location(10)]));
+        assert_eq!(predicate.uris().collect::<Vec<&str>>(), vec!["path/file.feature"]);
+    }
+
+    #[test]
+    pub fn rejects_invalid_selectors() {
+        assert!(LinePredicate::parse(&["path/file.feature"]).is_err());
+        assert!(LinePredicate::parse(&["path/file.feature:eight"]).is_err());
+        assert!(LinePredicate::parse(&[":8"]).is_err());
+    }
+
     fn location(line: u32) -> Location {
         Location {
             line,
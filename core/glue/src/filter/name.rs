@@ -5,19 +5,15 @@ pub struct NamePredicate {
 }
 
 impl NamePredicate {
-    pub fn new(patterns: &[&str]) -> NamePredicate {
+    pub fn new(patterns: &[&str]) -> Result<NamePredicate, String> {
         let name_patterns = patterns.iter()
-            .map(|pattern| {
-                match Regex::new(pattern) {
-                    Ok(regex) => regex,
-                    Err(error) => panic!("invalid name pattern: {}", error),
-                }
-            })
-            .collect();
+            .map(|pattern| Regex::new(pattern)
+                .map_err(|error| format!("name pattern \"{}\" is not a valid regex: {}", pattern, error)))
+            .collect::<Result<Vec<Regex>, String>>()?;
 
-        NamePredicate {
+        Ok(NamePredicate {
             patterns: name_patterns,
-        }
+        })
     }
 
     pub fn apply(&self, cuke_name: &str) -> bool {
@@ -37,29 +33,34 @@ mod tests {
 
     #[test]
     pub fn anchored_name_pattern_matches_exact_name() {
-        let predicate = NamePredicate::new(&["^a pickle name$"]);
+        let predicate = NamePredicate::new(&["^a pickle name$"]).unwrap();
 
         assert!(predicate.apply("a pickle name"));
     }
 
     #[test]
     pub fn anchored_name_pattern_does_not_match_part_of_name() {
-        let predicate = NamePredicate::new(&["^a pickle name$"]);
+        let predicate = NamePredicate::new(&["^a pickle name$"]).unwrap();
 
         assert!(!predicate.apply("a pickle name with suffix"));
     }
 
     #[test]
     pub fn non_anchored_name_pattern_matches_part_of_name() {
-        let predicate = NamePredicate::new(&["a pickle name"]);
+        let predicate = NamePredicate::new(&["a pickle name"]).unwrap();
 
         assert!(predicate.apply("a pickle name with suffix"));
     }
 
     #[test]
     pub fn wildcard_name_pattern_matches_part_of_name() {
-        let predicate = NamePredicate::new(&["a .* name"]);
+        let predicate = NamePredicate::new(&["a .* name"]).unwrap();
 
         assert!(predicate.apply("a pickleEvent name"));
     }
+
+    #[test]
+    pub fn invalid_name_pattern_is_an_error() {
+        assert!(NamePredicate::new(&["a (pickle name"]).is_err());
+    }
 }
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use gherkin::cuke::Tag;
 
 use self::expression::Expression;
@@ -8,19 +10,33 @@ pub mod parser;
 
 #[derive(Debug, Clone)]
 pub struct TagPredicate<'p> {
-    text: &'p str,
+    text: Cow<'p, str>,
     expression: Expression,
 }
 
 impl<'p> TagPredicate<'p> {
     pub fn new(tag_expression: &'p str) -> Result<TagPredicate<'p>, String> {
         let tag_predicate = TagPredicate {
-            text: tag_expression,
+            text: Cow::Borrowed(tag_expression),
             expression: parser::parse(tag_expression)?,
         };
         Ok(tag_predicate)
     }
 
+    /// Like `new`, but keeps the tag expression itself, so that it can outlive its source.
+    pub fn from_string(tag_expression: String) -> Result<TagPredicate<'static>, String> {
+        let tag_predicate = TagPredicate {
+            expression: parser::parse(&tag_expression)?,
+            text: Cow::Owned(tag_expression),
+        };
+        Ok(tag_predicate)
+    }
+
+    /// The tag expression of this predicate.
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
     pub fn apply(&self, tags: &[Tag<'_>]) -> bool {
         self.expression.evaluate(tags)
     }
@@ -40,6 +56,15 @@ mod tests {
         assert!(predicate.apply(&tags));
     }
 
+    #[test]
+    fn owned_tag_predicate_keeps_its_expression() {
+        let tags = create_tags(&["@FOO"]);
+        let predicate = TagPredicate::from_string("@FOO or @BAR".to_owned()).unwrap();
+
+        assert!(predicate.apply(&tags));
+        assert_eq!(predicate.as_str(), "@FOO or @BAR");
+    }
+
     #[test]
     fn single_tag_predicate_does_not_match_with_no_tags() {
         let tags = create_tags(&[]);
@@ -0,0 +1,39 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// Data that is shared by all scenarios of a test run.
+///
+/// It is filled by `#[before_all]` hooks, can be read by every scenario
+/// through [`Scenario::global`] and is handed to the `#[after_all]` hooks for cleanup.
+///
+/// In contrast to the scenario data, the global data is shared between threads
+/// and thus only accepts `Send + Sync` values.
+///
+/// [`Scenario::global`]: ../scenario/struct.Scenario.html#method.global
+#[derive(Debug, Default)]
+pub struct Global {
+    data: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl Global {
+    #[doc(hidden)]
+    pub fn new() -> Global {
+        Global {
+            data: HashMap::new(),
+        }
+    }
+
+    pub fn set<T: Send + Sync + 'static>(&mut self, data: T) {
+        self.data.insert(TypeId::of::<T>(), Box::new(data));
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.data.get(&TypeId::of::<T>())
+            .map(|value| value.downcast_ref::<T>().unwrap())
+    }
+
+    pub fn get_mut<T: Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        self.data.get_mut(&TypeId::of::<T>())
+            .map(|value| value.downcast_mut::<T>().unwrap())
+    }
+}
@@ -1,7 +1,10 @@
 use std::fmt;
 use std::str::FromStr;
+use std::time::Duration;
 
 use crate::error::ExecutionError;
+use crate::feature::Feature;
+use crate::global::Global;
 use crate::location::StaticGlueCodeLocation;
 use crate::scenario::Scenario;
 
@@ -9,6 +12,16 @@ use crate::scenario::Scenario;
 #[doc(hidden)]
 pub type HookFn = fn(&mut Scenario) -> ::std::result::Result<(), ExecutionError>;
 
+/// The type of a generated global hook handler (wraps a user defined `#[before_all]`
+/// or `#[after_all]` hook function).
+#[doc(hidden)]
+pub type GlobalHookFn = fn(&mut Global) -> ::std::result::Result<(), ExecutionError>;
+
+/// The type of a generated feature hook handler (wraps a user defined `#[before_feature]`
+/// or `#[after_feature]` hook function).
+#[doc(hidden)]
+pub type FeatureHookFn = fn(&mut Feature) -> ::std::result::Result<(), ExecutionError>;
+
 #[doc(hidden)]
 pub type HookFnLocationFn = fn() -> StaticGlueCodeLocation;
 
@@ -42,6 +55,8 @@ pub struct StaticHookDef {
     /// (@smoke or @ui) and (not @slow)
     /// ```
     pub tag_expression: &'static str,
+    /// The maximum duration of the hook, overrides the default step timeout of the config.
+    pub timeout: Option<Duration>,
     /// The generated hook handler function that will call the user defined annotated function.
     pub hook_fn: HookFn,
     /// The generated hook location function that will return
@@ -54,6 +69,73 @@ impl fmt::Debug for StaticHookDef {
         f.debug_struct("StaticHookDef")
             .field("name", &self.name)
             .field("tag_expression", &self.tag_expression)
+            .field("timeout", &self.timeout)
+            .field("hook_fn", &"<hook_fn>")
+            .field("hook_fn_location_fn", &"<hook_fn_location_fn>")
+            .finish()
+    }
+}
+
+/// Generated info for a global hook definition
+/// (for example a `#[before_all(...)]` annotated function).
+#[doc(hidden)]
+pub struct StaticGlobalHookDef {
+    /// Name of the hook definition function.
+    pub name: &'static str,
+    /// Execution order of the hook definition function of the same hook type.
+    ///
+    /// Before hooks are executed in ascending order (..., -1, 0, 1, ...) and
+    /// after hooks are executed in descending order (..., 1, 0, -1, ...).
+    pub order: isize,
+    /// The generated hook handler function that will call the user defined annotated function.
+    pub hook_fn: GlobalHookFn,
+    /// The generated hook location function that will return
+    /// the location of the user defined annotated function.
+    pub hook_fn_location_fn: HookFnLocationFn,
+}
+
+impl fmt::Debug for StaticGlobalHookDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        f.debug_struct("StaticGlobalHookDef")
+            .field("name", &self.name)
+            .field("order", &self.order)
+            .field("hook_fn", &"<hook_fn>")
+            .field("hook_fn_location_fn", &"<hook_fn_location_fn>")
+            .finish()
+    }
+}
+
+/// Generated info for a feature hook definition
+/// (for example a `#[before_feature(...)]` annotated function).
+#[doc(hidden)]
+pub struct StaticFeatureHookDef {
+    /// Name of the hook definition function.
+    pub name: &'static str,
+    /// Execution order of the hook definition function of the same hook type.
+    ///
+    /// Before hooks are executed in ascending order (..., -1, 0, 1, ...) and
+    /// after hooks are executed in descending order (..., 1, 0, -1, ...).
+    pub order: isize,
+    /// A tag expression to restrict for which features the hook should be executed.
+    ///
+    /// The hook is executed for a feature if the tags of at least one of
+    /// its executed scenarios match the expression, see [`StaticHookDef::tag_expression`].
+    ///
+    /// [`StaticHookDef::tag_expression`]: struct.StaticHookDef.html#structfield.tag_expression
+    pub tag_expression: &'static str,
+    /// The generated hook handler function that will call the user defined annotated function.
+    pub hook_fn: FeatureHookFn,
+    /// The generated hook location function that will return
+    /// the location of the user defined annotated function.
+    pub hook_fn_location_fn: HookFnLocationFn,
+}
+
+impl fmt::Debug for StaticFeatureHookDef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        f.debug_struct("StaticFeatureHookDef")
+            .field("name", &self.name)
+            .field("order", &self.order)
+            .field("tag_expression", &self.tag_expression)
             .field("hook_fn", &"<hook_fn>")
             .field("hook_fn_location_fn", &"<hook_fn_location_fn>")
             .finish()
@@ -63,10 +145,14 @@ impl fmt::Debug for StaticHookDef {
 /// A hook type like "BeforeScenario" or "AfterStep".
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
 pub enum HookType {
+    BeforeAll,
+    BeforeFeature,
     BeforeScenario,
     BeforeStep,
     AfterStep,
     AfterScenario,
+    AfterFeature,
+    AfterAll,
 }
 
 impl HookType {
@@ -75,10 +161,38 @@ impl HookType {
         use self::HookType::*;
 
         match self {
+            BeforeAll => "BeforeAll",
+            BeforeFeature => "BeforeFeature",
             BeforeScenario => "BeforeScenario",
             BeforeStep => "BeforeStep",
             AfterStep => "AfterStep",
             AfterScenario => "AfterScenario",
+            AfterFeature => "AfterFeature",
+            AfterAll => "AfterAll",
+        }
+    }
+
+    /// Returns whether hooks of this type run once for the whole test run
+    /// instead of once per scenario or step.
+    #[inline]
+    pub fn is_global(self) -> bool {
+        use self::HookType::*;
+
+        match self {
+            BeforeAll | AfterAll => true,
+            BeforeFeature | BeforeScenario | BeforeStep | AfterStep | AfterScenario | AfterFeature => false,
+        }
+    }
+
+    /// Returns whether hooks of this type run once per feature file
+    /// instead of once per scenario or step.
+    #[inline]
+    pub fn is_feature(self) -> bool {
+        use self::HookType::*;
+
+        match self {
+            BeforeFeature | AfterFeature => true,
+            BeforeAll | BeforeScenario | BeforeStep | AfterStep | AfterScenario | AfterAll => false,
         }
     }
 }
@@ -90,10 +204,14 @@ impl FromStr for HookType {
         use self::HookType::*;
 
         match s {
+            "BeforeAll" => Ok(BeforeAll),
+            "BeforeFeature" => Ok(BeforeFeature),
             "BeforeScenario" => Ok(BeforeScenario),
             "BeforeStep" => Ok(BeforeStep),
             "AfterStep" => Ok(AfterStep),
             "AfterScenario" => Ok(AfterScenario),
+            "AfterFeature" => Ok(AfterFeature),
+            "AfterAll" => Ok(AfterAll),
             _ => Err(()),
         }
     }
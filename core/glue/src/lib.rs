@@ -2,6 +2,8 @@
 
 pub mod error;
 pub mod scenario;
+pub mod global;
+pub mod feature;
 #[doc(hidden)]
 pub mod location;
 #[doc(hidden)]
@@ -9,12 +11,26 @@ pub mod filter;
 pub mod step;
 pub mod hook;
 pub mod panic;
+pub mod timeout;
 
 #[doc(hidden)]
 pub struct StaticGlueDefinitions {
+    pub before_all_hooks: &'static [&'static hook::StaticGlobalHookDef],
+    pub before_feature_hooks: &'static [&'static hook::StaticFeatureHookDef],
     pub before_scenario_hooks: &'static [&'static hook::StaticHookDef],
     pub before_step_hooks: &'static [&'static hook::StaticHookDef],
     pub steps: &'static [&'static step::StaticStepDef],
+    pub parameter_types: &'static [&'static step::parameter_type::StaticParameterTypeDef],
     pub after_step_hooks: &'static [&'static hook::StaticHookDef],
     pub after_scenario_hooks: &'static [&'static hook::StaticHookDef],
+    pub after_feature_hooks: &'static [&'static hook::StaticFeatureHookDef],
+    pub after_all_hooks: &'static [&'static hook::StaticGlobalHookDef],
+}
+
+/// A feature file that was embedded into the test binary by the `embed_features!` macro.
+#[derive(Debug, Copy, Clone)]
+pub struct EmbeddedFeatureFile {
+    /// The path of the feature file relative to the embedded features directory.
+    pub uri: &'static str,
+    pub source: &'static str,
 }
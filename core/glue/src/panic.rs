@@ -10,6 +10,17 @@ lazy_static! {
     static ref PANIC_INFOS: RwLock<HashMap<ThreadId, PanicInfo>> = RwLock::new(HashMap::new());
 }
 
+/// Functions of the cuke runner that execute glue code.
+///
+/// These functions need to be annotated with `#[inline(never)]`
+/// to prevent the call from being optimized out.
+const GLUE_CODE_EXECUTING_FNS: &[&str] = &[
+    "cuke_runner::runner::test_step::run_test_step",
+    "cuke_runner::runner::test_step::execute_step_job",
+    "cuke_runner::runner::test_step::run_global_hook",
+    "cuke_runner::runner::test_step::run_feature_hook",
+];
+
 /// A struct providing information about a panic.
 ///
 /// This is basically a owned variant of the `PanicInfo` from the Rust Standard Library.
@@ -243,10 +254,9 @@ pub fn register_cuke_runner_hook() {
                     .iter()
                     .any(|symbol| {
                         if let Some(symbol_name) = symbol.name() {
-                            // function needs to be annotated with `#[inline(never)]`
-                            // to prevent the call from being optimized out
-                            symbol_name.to_string().starts_with(
-                                "cuke_runner::runner::test_step::run_test_step")
+                            let symbol_name = symbol_name.to_string();
+                            GLUE_CODE_EXECUTING_FNS.iter()
+                                .any(|glue_code_executing_fn| symbol_name.starts_with(glue_code_executing_fn))
                         } else {
                             false
                         }
@@ -1,34 +1,107 @@
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::Arc;
 
 use failure::Fail;
 
+use crate::feature::Feature;
+use crate::global::Global;
+
+/// Data of a single scenario run.
+///
+/// Steps and hooks with a timeout are run on their own thread,
+/// so they can only use the data that was set with `set_send`.
 #[derive(Debug, Default)]
 pub struct Scenario {
     data: HashMap<TypeId, Box<dyn Any>>,
+    send_data: HashMap<TypeId, Box<dyn Any + Send>>,
+    feature: Arc<Feature>,
+}
+
+/// The `Send` data of a scenario, that is moved to the thread
+/// of a step or hook with a timeout.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct SendScenario {
+    send_data: HashMap<TypeId, Box<dyn Any + Send>>,
+    feature: Arc<Feature>,
 }
 
 impl Scenario {
     #[doc(hidden)]
-    pub fn new() -> Scenario {
+    pub fn new(feature: Arc<Feature>) -> Scenario {
         Scenario {
             data: HashMap::new(),
+            send_data: HashMap::new(),
+            feature,
         }
     }
 
+    /// Returns the data that is shared by all scenarios of the test run.
+    pub fn global(&self) -> &Global {
+        self.feature.global()
+    }
+
+    /// Returns the data that is shared by all scenarios of the current feature file.
+    pub fn feature(&self) -> &Feature {
+        &self.feature
+    }
+
     pub fn set<T: 'static>(&mut self, data: T) {
+        self.send_data.remove(&TypeId::of::<T>());
         self.data.insert(TypeId::of::<T>(), Box::new(data));
     }
 
+    /// Sets data that can also be used by steps and hooks with a timeout.
+    pub fn set_send<T: Send + 'static>(&mut self, data: T) {
+        self.data.remove(&TypeId::of::<T>());
+        self.send_data.insert(TypeId::of::<T>(), Box::new(data));
+    }
+
     pub fn get<T: 'static>(&self) -> Option<&T> {
-        self.data.get(&TypeId::of::<T>())
-            .map(|value| value.downcast_ref::<T>().unwrap())
+        let type_id = TypeId::of::<T>();
+
+        match self.data.get(&type_id) {
+            Some(value) => Some(value.downcast_ref::<T>().unwrap()),
+            None => self.send_data.get(&type_id)
+                .map(|value| value.downcast_ref::<T>().unwrap()),
+        }
     }
 
     pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
-        self.data.get_mut(&TypeId::of::<T>())
-            .map(|value| value.downcast_mut::<T>().unwrap())
+        let type_id = TypeId::of::<T>();
+
+        match self.data.get_mut(&type_id) {
+            Some(value) => Some(value.downcast_mut::<T>().unwrap()),
+            None => self.send_data.get_mut(&type_id)
+                .map(|value| value.downcast_mut::<T>().unwrap()),
+        }
+    }
+
+    /// Whether data was set with `set` that cannot be moved to the thread of a step with a timeout.
+    #[doc(hidden)]
+    pub fn has_non_send_data(&self) -> bool {
+        !self.data.is_empty()
+    }
+
+    /// Converts the scenario into its `Send` data, the data that was set with `set` is dropped.
+    #[doc(hidden)]
+    pub fn into_send(self) -> SendScenario {
+        SendScenario {
+            send_data: self.send_data,
+            feature: self.feature,
+        }
+    }
+}
+
+impl From<SendScenario> for Scenario {
+    fn from(send_scenario: SendScenario) -> Scenario {
+        Scenario {
+            data: HashMap::new(),
+            send_data: send_scenario.send_data,
+            feature: send_scenario.feature,
+        }
     }
 }
 
@@ -82,6 +155,18 @@ impl<'a> FromScenarioMut<'a> for &'a mut Scenario {
     }
 }
 
+impl<'a> FromScenario<'a> for &'a Global {
+    fn from_scenario(scenario: &'a Scenario) -> FromScenarioResult<&'a Global> {
+        Ok(scenario.global())
+    }
+}
+
+impl<'a> FromScenario<'a> for &'a Feature {
+    fn from_scenario(scenario: &'a Scenario) -> FromScenarioResult<&'a Feature> {
+        Ok(scenario.feature())
+    }
+}
+
 impl<'a, T: 'static> FromScenario<'a> for Option<&'a T> {
     fn from_scenario(scenario: &'a Scenario) -> FromScenarioResult<Option<&'a T>> {
         Ok(scenario.get::<T>())
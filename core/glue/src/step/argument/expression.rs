@@ -50,6 +50,7 @@ pub struct Expression<'s> {
     value: &'s str,
     start: usize,
     end: usize,
+    parameter_type: Option<&'static str>,
 }
 
 impl<'s> Expression<'s> {
@@ -65,6 +66,21 @@ impl<'s> Expression<'s> {
     pub fn end(&self) -> usize {
         self.end
     }
+    /// Returns the name of the parameter type that matched the value,
+    /// like `int` for `{int}` or `color` for a user-defined parameter type.
+    pub fn parameter_type(&self) -> Option<&'static str> {
+        self.parameter_type
+    }
+}
+
+#[doc(hidden)]
+impl<'s> Expression<'s> {
+    pub fn with_parameter_type<'t: 's>(mat: regex::Match<'t>, parameter_type: Option<&'static str>) -> Self {
+        Expression {
+            parameter_type,
+            ..Expression::from(mat)
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -74,6 +90,7 @@ impl<'s, 't: 's> From<regex::Match<'t>> for Expression<'s> {
             value: mat.as_str(),
             start: mat.start(),
             end: mat.end(),
+            parameter_type: None,
         }
     }
 }
@@ -79,15 +79,19 @@ impl From<String> for FromStepArgumentError {
 
 impl<'s, T: FromStr> FromStepArgument<'s> for T where <T as std::str::FromStr>::Err: fmt::Debug {
     fn from_step_argument(step_argument: &'s StepArgument<'_>) -> FromStepArgumentResult<T> {
-        let str_value = match step_argument {
-            StepArgument::Expression(expression) => Some(expression.value()),
-            StepArgument::DocString(doc_string) => Some(doc_string.value()),
-            StepArgument::DataTable(_data_table) => None,
+        let (str_value, parameter_type) = match step_argument {
+            StepArgument::Expression(expression) => (Some(expression.value()), expression.parameter_type()),
+            StepArgument::DocString(doc_string) => (Some(doc_string.value()), None),
+            StepArgument::DataTable(_data_table) => (None, None),
         };
 
         match str_value {
             Some(value) => value.parse()
-                .map_err(|err| FromStepArgumentError::new(format!("{:?}", err))),
+                .map_err(|err| match parameter_type {
+                    Some(parameter_type) => FromStepArgumentError::new(format!(
+                        "cannot convert \"{}\" to parameter type {{{}}}: {:?}", value, parameter_type, err)),
+                    None => FromStepArgumentError::new(format!("{:?}", err)),
+                }),
             None => {
                 Err(FromStepArgumentError::new(
                     "cannot parse DataTable, use DataTable itself as argument type".to_owned()
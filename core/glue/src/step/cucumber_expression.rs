@@ -0,0 +1,403 @@
+//! [Cucumber Expressions](https://github.com/cucumber/cucumber-expressions) like
+//! `I have {int} cuke(s) in my belly/stomach` that are compiled to regexes.
+
+use std::borrow::Cow;
+
+use crate::step::parameter_type::{self, StaticParameterTypeDef};
+
+const ESCAPING_CHAR: char = '\\';
+
+/// The type of a parameter of a cucumber expression like `{int}`.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum ParameterType {
+    /// `{int}` matches integers like `71` or `-19`.
+    Int,
+    /// `{float}` matches floats like `3.6`, `.8` or `-9.2`.
+    Float,
+    /// `{word}` matches words without whitespace like `banana`.
+    Word,
+    /// `{string}` matches single or double quoted strings like `"banana split"`.
+    ///
+    /// The quotes are not part of the matched value.
+    String,
+    /// `{}` matches anything.
+    Anonymous,
+    /// A user-defined parameter type like `{color}`
+    /// that was declared with the `#[parameter_type]` attribute.
+    Custom(&'static StaticParameterTypeDef),
+}
+
+impl ParameterType {
+    /// Returns the built-in parameter type with the name that is used between the braces.
+    pub fn from_name(name: &str) -> Option<ParameterType> {
+        use self::ParameterType::*;
+
+        match name {
+            "int" => Some(Int),
+            "float" => Some(Float),
+            "word" => Some(Word),
+            "string" => Some(String),
+            "" => Some(Anonymous),
+            _ => None,
+        }
+    }
+
+    /// Returns the name that is used between the braces.
+    pub fn name(self) -> &'static str {
+        use self::ParameterType::*;
+
+        match self {
+            Int => "int",
+            Float => "float",
+            Word => "word",
+            String => "string",
+            Anonymous => "",
+            Custom(parameter_type) => parameter_type.name,
+        }
+    }
+
+    /// Returns the regex of the parameter type.
+    ///
+    /// The regex has one capture group that participates in a match,
+    /// even if it has more than one capture group.
+    pub fn regex(self) -> Cow<'static, str> {
+        use self::ParameterType::*;
+
+        match self {
+            Int => Cow::Borrowed(r"(-?\d+)"),
+            Float => Cow::Borrowed(r"([-+]?(?:\d+\.\d*|\.?\d+)(?:[eE][-+]?\d+)?)"),
+            Word => Cow::Borrowed(r"([^\s]+)"),
+            String => Cow::Borrowed(r#"(?:"([^"\\]*(?:\\.[^"\\]*)*)"|'([^'\\]*(?:\\.[^'\\]*)*)')"#),
+            Anonymous => Cow::Borrowed(r"(.*)"),
+            Custom(parameter_type) => Cow::Owned(format!("({})", parameter_type.regex)),
+        }
+    }
+}
+
+/// A cucumber expression that was compiled to an anchored regex.
+#[derive(Debug, Clone)]
+pub struct CucumberExpression {
+    regex: String,
+    parameter_types: Vec<ParameterType>,
+}
+
+impl CucumberExpression {
+    /// Parses a cucumber expression that only uses built-in parameter types.
+    pub fn parse(expression: &str) -> Result<CucumberExpression, String> {
+        CucumberExpression::parse_with(expression, ParameterType::from_name)
+    }
+
+    /// Parses a cucumber expression that uses built-in or the given user-defined parameter types.
+    pub fn parse_with_parameter_types(expression: &str, parameter_types: &[&'static StaticParameterTypeDef])
+        -> Result<CucumberExpression, String>
+    {
+        CucumberExpression::parse_with(expression, |name| {
+            ParameterType::from_name(name).or_else(|| parameter_types.iter()
+                .find(|parameter_type| parameter_type.name == name)
+                .map(|parameter_type| ParameterType::Custom(*parameter_type)))
+        })
+    }
+
+    /// Parses a cucumber expression and looks up its parameter types by name.
+    pub fn parse_with<F>(expression: &str, parameter_type: F) -> Result<CucumberExpression, String>
+        where F: Fn(&str) -> Option<ParameterType>
+    {
+        let tokens = tokenize(expression)?;
+
+        let mut regex = String::with_capacity(expression.len() * 2);
+        let mut parameter_types = Vec::new();
+        regex.push('^');
+
+        for group in split_whitespace(&tokens) {
+            match group {
+                Group::Whitespace(whitespace) => regex.push_str(&regex::escape(whitespace)),
+                Group::Tokens(tokens) => {
+                    compile_tokens(tokens, &parameter_type, &mut regex, &mut parameter_types)?
+                }
+            }
+        }
+
+        regex.push('$');
+
+        Ok(CucumberExpression {
+            regex,
+            parameter_types,
+        })
+    }
+
+    /// Returns the regex that the cucumber expression was compiled to.
+    pub fn regex(&self) -> &str {
+        &self.regex
+    }
+
+    /// Returns the types of the parameters in the order of their captured step arguments.
+    pub fn parameter_types(&self) -> &[ParameterType] {
+        &self.parameter_types
+    }
+}
+
+/// Returns whether the step expression is a cucumber expression rather than a regex.
+///
+/// Expressions that are anchored with `^` or `$` are regexes, other expressions are
+/// cucumber expressions if they contain a parameter like `{int}`, `{color}` or `{}`.
+/// Expressions without parameters (e.g. only with optional text) are treated as regexes,
+/// so that cucumber expressions need to be opted into for them.
+pub fn is_cucumber_expression(expression: &str) -> bool {
+    if expression.starts_with('^') || expression.ends_with('$') {
+        return false;
+    }
+
+    let mut rest = expression;
+    while let Some(start) = rest.find('{') {
+        let parameter = &rest[start + 1..];
+        match parameter.find('}') {
+            Some(end) => {
+                let name = &parameter[..end];
+                if name.is_empty() || parameter_type::is_valid_name(name) {
+                    return true;
+                }
+                rest = &parameter[end + 1..];
+            }
+            None => return false,
+        }
+    }
+
+    false
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Token {
+    Text(String),
+    Whitespace(String),
+    Optional(String),
+    Parameter(String),
+    Alternation,
+}
+
+enum Group<'t> {
+    Whitespace(&'t str),
+    Tokens(&'t [Token]),
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ESCAPING_CHAR => match chars.next() {
+                Some(escaped) => push_text(&mut tokens, escaped),
+                None => return Err(format!("\"{}\" ends with an escaping backslash", expression)),
+            },
+            '(' => {
+                let text = read_until(&mut chars, ')')
+                    .ok_or_else(|| format!("optional text in \"{}\" is not closed with ')' \
+                        or contains a parameter or alternative text", expression))?;
+                if text.is_empty() {
+                    return Err(format!("optional text in \"{}\" must not be empty", expression));
+                }
+                tokens.push(Token::Optional(text));
+            }
+            '{' => {
+                let name = read_until(&mut chars, '}')
+                    .ok_or_else(|| format!("parameter in \"{}\" is not closed with '}}'", expression))?;
+                tokens.push(Token::Parameter(name));
+            }
+            ')' | '}' => {
+                return Err(format!("unmatched '{}' in \"{}\", use '\\{}' to match it literally",
+                    c, expression, c));
+            }
+            '/' => tokens.push(Token::Alternation),
+            c if c.is_whitespace() => match tokens.last_mut() {
+                Some(Token::Whitespace(whitespace)) => whitespace.push(c),
+                _ => tokens.push(Token::Whitespace(c.to_string())),
+            },
+            c => push_text(&mut tokens, c),
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn push_text(tokens: &mut Vec<Token>, c: char) {
+    match tokens.last_mut() {
+        Some(Token::Text(text)) => text.push(c),
+        _ => tokens.push(Token::Text(c.to_string())),
+    }
+}
+
+/// Reads the (unescaped) text up to the `end` char,
+/// returns `None` if the text is not closed or contains special chars.
+fn read_until(chars: &mut std::str::Chars<'_>, end: char) -> Option<String> {
+    let mut text = String::new();
+
+    while let Some(c) = chars.next() {
+        match c {
+            ESCAPING_CHAR => text.push(chars.next()?),
+            c if c == end => return Some(text),
+            '(' | ')' | '{' | '}' | '/' => return None,
+            c => text.push(c),
+        }
+    }
+
+    None
+}
+
+fn split_whitespace(tokens: &[Token]) -> Vec<Group<'_>> {
+    let mut groups = Vec::new();
+    let mut start = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        if let Token::Whitespace(whitespace) = token {
+            if start < index {
+                groups.push(Group::Tokens(&tokens[start..index]));
+            }
+            groups.push(Group::Whitespace(whitespace));
+            start = index + 1;
+        }
+    }
+
+    if start < tokens.len() {
+        groups.push(Group::Tokens(&tokens[start..]));
+    }
+
+    groups
+}
+
+fn compile_tokens<F>(tokens: &[Token], parameter_type: &F, regex: &mut String,
+    parameter_types: &mut Vec<ParameterType>) -> Result<(), String>
+    where F: Fn(&str) -> Option<ParameterType>
+{
+    if !tokens.contains(&Token::Alternation) {
+        for token in tokens {
+            compile_token(token, parameter_type, regex, parameter_types)?;
+        }
+        return Ok(());
+    }
+
+    regex.push_str("(?:");
+    for (index, alternative) in tokens.split(|token| *token == Token::Alternation).enumerate() {
+        if !alternative.iter().any(|token| matches!(token, Token::Text(_))) {
+            return Err("alternative text must contain text that is not optional".to_owned());
+        }
+        if alternative.iter().any(|token| matches!(token, Token::Parameter(_))) {
+            return Err("alternative text must not contain a parameter".to_owned());
+        }
+
+        if index > 0 {
+            regex.push('|');
+        }
+        for token in alternative {
+            compile_token(token, parameter_type, regex, parameter_types)?;
+        }
+    }
+    regex.push(')');
+
+    Ok(())
+}
+
+fn compile_token<F>(token: &Token, parameter_type: &F, regex: &mut String,
+    parameter_types: &mut Vec<ParameterType>) -> Result<(), String>
+    where F: Fn(&str) -> Option<ParameterType>
+{
+    match token {
+        Token::Text(text) => regex.push_str(&regex::escape(text)),
+        Token::Optional(text) => {
+            regex.push_str("(?:");
+            regex.push_str(&regex::escape(text));
+            regex.push_str(")?");
+        }
+        Token::Parameter(name) => {
+            let parameter_type = parameter_type(name)
+                .ok_or_else(|| format!("undefined parameter type {{{}}}", name))?;
+            regex.push_str(&parameter_type.regex());
+            parameter_types.push(parameter_type);
+        }
+        Token::Whitespace(whitespace) => regex.push_str(&regex::escape(whitespace)),
+        Token::Alternation => unreachable!("alternations are compiled as groups"),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::*;
+
+    fn captures(expression: &str, text: &str) -> Option<Vec<String>> {
+        let cucumber_expression = CucumberExpression::parse(expression).unwrap();
+        let regex = Regex::new(cucumber_expression.regex()).unwrap();
+
+        regex.captures(text).map(|captures| captures.iter()
+            .skip(1)
+            .filter_map(|capture| capture.map(|capture| capture.as_str().to_owned()))
+            .collect())
+    }
+
+    #[test]
+    fn compiles_parameters() {
+        let expression = CucumberExpression::parse("I have {int} cukes and {float} {word} in {string}").unwrap();
+        assert_eq!(expression.parameter_types(), &[
+            ParameterType::Int, ParameterType::Float, ParameterType::Word, ParameterType::String,
+        ]);
+
+        assert_eq!(captures("I have {int} cukes", "I have -42 cukes"), Some(vec!["-42".to_owned()]));
+        assert_eq!(captures("it costs {float}", "it costs .5"), Some(vec![".5".to_owned()]));
+        assert_eq!(captures("I eat a {word}", "I eat a banana"), Some(vec!["banana".to_owned()]));
+        assert_eq!(captures("I say {string}", "I say 'hi there'"), Some(vec!["hi there".to_owned()]));
+        assert_eq!(captures("I say {string}", "I say \"\""), Some(vec!["".to_owned()]));
+        assert_eq!(captures("anything {}", "anything goes"), Some(vec!["goes".to_owned()]));
+        assert_eq!(captures("I have {int} cukes", "I have many cukes"), None);
+    }
+
+    #[test]
+    fn compiles_optional_and_alternative_text() {
+        assert_eq!(captures("I have {int} cuke(s)", "I have 1 cuke"), Some(vec!["1".to_owned()]));
+        assert_eq!(captures("I have {int} cuke(s)", "I have 2 cukes"), Some(vec!["2".to_owned()]));
+        assert!(captures("in my belly/stomach", "in my stomach").is_some());
+        assert!(captures("in my belly/stomach", "in my belly").is_some());
+        assert!(captures("in my belly/stomach", "in my belly/stomach").is_none());
+        assert!(captures("I eat a cuke(s)/gherkin", "I eat a cukes").is_some());
+    }
+
+    #[test]
+    fn compiles_user_defined_parameters() {
+        use crate::step::parameter_type::COLOR;
+
+        let expression = CucumberExpression::parse_with_parameter_types("I paint it {color} {int} times", &[&COLOR])
+            .unwrap();
+        assert_eq!(expression.regex(), r"^I paint it (red|green|blue) (-?\d+) times$");
+        assert_eq!(expression.parameter_types(), &[ParameterType::Custom(&COLOR), ParameterType::Int]);
+
+        assert!(CucumberExpression::parse_with_parameter_types("I paint it {colour}", &[&COLOR]).is_err());
+    }
+
+    #[test]
+    fn escapes_special_chars() {
+        assert!(captures("a \\(literal\\) \\{int\\} 1\\/2 $.*", "a (literal) {int} 1/2 $.*").is_some());
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(CucumberExpression::parse("{color}").is_err());
+        assert!(CucumberExpression::parse("a cuke(s").is_err());
+        assert!(CucumberExpression::parse("a cuke()").is_err());
+        assert!(CucumberExpression::parse("a ({int})").is_err());
+        assert!(CucumberExpression::parse("a {int}/b").is_err());
+        assert!(CucumberExpression::parse("a /b").is_err());
+        assert!(CucumberExpression::parse("a}").is_err());
+    }
+
+    #[test]
+    fn detects_cucumber_expressions() {
+        assert!(is_cucumber_expression("I have {int} cukes"));
+        assert!(is_cucumber_expression("I say {}"));
+        assert!(is_cucumber_expression("I paint it {color}"));
+        assert!(!is_cucumber_expression("I add (\\d+) and (\\d+)"));
+        assert!(!is_cucumber_expression("^I have {int} cukes$"));
+        assert!(!is_cucumber_expression("a{2,3}"));
+        assert!(!is_cucumber_expression("I have a cuke(s)"));
+    }
+}
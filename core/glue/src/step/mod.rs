@@ -1,5 +1,6 @@
 use std::fmt;
 use std::str::FromStr;
+use std::time::Duration;
 
 use crate::error::ExecutionError;
 use crate::location::StaticGlueCodeLocation;
@@ -7,6 +8,8 @@ use crate::scenario::Scenario;
 use crate::step::argument::StepArgument;
 
 pub mod argument;
+pub mod cucumber_expression;
+pub mod parameter_type;
 
 /// The type of a step handler (wraps a user defined step function).
 #[doc(hidden)]
@@ -24,6 +27,13 @@ pub struct StaticStepDef {
     pub keyword: StepKeyword,
     /// The step definition expression to match a step in a cucumber scenario.
     pub expression: &'static str,
+    /// Whether the expression is a regex or a cucumber expression.
+    pub expression_type: ExpressionType,
+    /// The type names of the step function parameters that take step arguments
+    /// (`#[scenario]` parameters are not included).
+    pub parameter_types: &'static [&'static str],
+    /// The maximum duration of the step, overrides the default step timeout of the config.
+    pub timeout: Option<Duration>,
     /// The generated step handler function that will call the user defined annotated function.
     pub step_fn: StepFn,
     /// The generated step location function that will return
@@ -37,12 +47,24 @@ impl fmt::Debug for StaticStepDef {
             .field("name", &self.name)
             .field("keyword", &self.keyword)
             .field("expression", &self.expression)
+            .field("expression_type", &self.expression_type)
+            .field("parameter_types", &self.parameter_types)
+            .field("timeout", &self.timeout)
             .field("step_fn", &"<step_fn>")
             .field("step_fn_location_fn", &"<step_fn_location_fn>")
             .finish()
     }
 }
 
+/// The type of a step definition expression.
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
+pub enum ExpressionType {
+    /// A regex like `I have (\d+) cukes`.
+    Regex,
+    /// A cucumber expression like `I have {int} cuke(s)`.
+    CucumberExpression,
+}
+
 /// A step keyword like "Given", "When" and "Then".
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
 pub enum StepKeyword {
@@ -0,0 +1,115 @@
+//! User-defined parameter types like `color` that can be referenced by step expressions,
+//! as `{color}` in cucumber expressions or as `(?P<color>)` placeholder in regexes.
+
+use crate::step::cucumber_expression::ParameterType;
+
+/// Generated info for a user-defined parameter type (a `#[parameter_type(...)]` annotated type).
+#[doc(hidden)]
+#[derive(Debug, Eq, PartialEq, Hash)]
+pub struct StaticParameterTypeDef {
+    /// The name that step expressions use to reference the parameter type.
+    pub name: &'static str,
+    /// The regex that matches the step text of the parameter type, without capture groups.
+    pub regex: &'static str,
+    /// The name of the annotated type that the matched step text is converted to.
+    pub type_name: &'static str,
+}
+
+/// Returns whether the name can be used for a user-defined parameter type.
+///
+/// Names start with a letter or an underscore and only contain alphanumeric chars
+/// or underscores, so that they are not confused with regex repetitions like `{2,3}`.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(c) if c.is_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+
+    chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Returns the regex placeholder like `(?P<color>)` for a parameter type with the name.
+pub fn regex_placeholder(name: &str) -> String {
+    format!("(?P<{}>)", name)
+}
+
+/// Replaces the `(?P<name>)` placeholders of the regex with the regexes of the parameter types.
+///
+/// Returns the resulting regex and the parameter types of the placeholders by capture group name,
+/// or an error if a placeholder references an undefined parameter type.
+pub fn resolve_regex_placeholders(regex: &str, parameter_types: &[&'static StaticParameterTypeDef])
+    -> Result<(String, Vec<(&'static str, ParameterType)>), String>
+{
+    let mut resolved_regex = regex.to_owned();
+    let mut placeholder_types = Vec::new();
+
+    for name in placeholder_names(regex) {
+        let placeholder = regex_placeholder(name);
+        let parameter_type = parameter_types.iter()
+            .copied()
+            .find(|parameter_type| parameter_type.name == name)
+            .ok_or_else(|| format!("undefined parameter type {}", placeholder))?;
+
+        let group = format!("(?P<{}>{})", parameter_type.name, parameter_type.regex);
+        resolved_regex = resolved_regex.replace(&placeholder, &group);
+        placeholder_types.push((parameter_type.name, ParameterType::Custom(parameter_type)));
+    }
+
+    Ok((resolved_regex, placeholder_types))
+}
+
+/// Returns the names of the `(?P<name>)` placeholders of the regex,
+/// named capture groups with a regex like `(?P<count>\d+)` are not placeholders.
+fn placeholder_names(regex: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = regex;
+
+    while let Some(start) = rest.find("(?P<") {
+        rest = &rest[start + 4..];
+        if let Some(end) = rest.find(">)") {
+            let name = &rest[..end];
+            if is_valid_name(name) && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+
+    names
+}
+
+/// The `color` parameter type of the tests.
+#[cfg(test)]
+pub(crate) static COLOR: StaticParameterTypeDef = StaticParameterTypeDef {
+    name: "color",
+    regex: "red|green|blue",
+    type_name: "Color",
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_names() {
+        assert!(is_valid_name("color"));
+        assert!(is_valid_name("_rgb_color2"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("2"));
+        assert!(!is_valid_name("2,3"));
+        assert!(!is_valid_name("light color"));
+    }
+
+    #[test]
+    fn resolves_regex_placeholders() {
+        let (regex, placeholder_types) = resolve_regex_placeholders(
+            "^I paint it (?P<color>) and (?P<times>\\d+) times$", &[&COLOR]).unwrap();
+
+        assert_eq!(regex, "^I paint it (?P<color>red|green|blue) and (?P<times>\\d+) times$");
+        assert_eq!(placeholder_types, vec![("color", ParameterType::Custom(&COLOR))]);
+
+        let result = resolve_regex_placeholders("^I paint it (?P<shade>)$", &[&COLOR]);
+        assert_eq!(result.unwrap_err(), "undefined parameter type (?P<shade>)");
+    }
+}
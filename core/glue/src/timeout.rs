@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+/// Parses a timeout like `"500ms"`, `"5s"`, `"2m"` or `"1h"`.
+///
+/// The same format is used for the `timeout` attribute parameter of steps and hooks
+/// and for the `@timeout(...)` tag of scenarios.
+pub fn parse_timeout(timeout: &str) -> Result<Duration, String> {
+    let timeout = timeout.trim();
+    let unit_start = timeout.find(|c: char| !c.is_ascii_digit())
+        .unwrap_or_else(|| timeout.len());
+    let (value, unit) = timeout.split_at(unit_start);
+
+    let value = value.parse::<u64>()
+        .map_err(|_| format!("timeout \"{}\" must start with a number", timeout))?;
+
+    let seconds_per_unit = match unit.trim() {
+        "ms" => return Ok(Duration::from_millis(value)),
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "" => return Err(format!("timeout \"{}\" is missing a unit (ms, s, m or h)", timeout)),
+        unit => return Err(format!("timeout \"{}\" has an unknown unit \"{}\" (expected ms, s, m or h)", timeout, unit)),
+    };
+
+    value.checked_mul(seconds_per_unit)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("timeout \"{}\" is too large", timeout))
+}
+
+/// Returns the timeout of a `@timeout(...)` tag
+/// or `None` if the tag is not a timeout tag.
+pub fn parse_timeout_tag(tag: &str) -> Option<Result<Duration, String>> {
+    if tag.starts_with("@timeout(") && tag.ends_with(')') {
+        let timeout = &tag["@timeout(".len()..tag.len() - 1];
+        Some(parse_timeout(timeout))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_timeouts_with_units() {
+        assert_eq!(parse_timeout("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_timeout("5s"), Ok(Duration::from_secs(5)));
+        assert_eq!(parse_timeout("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_timeout("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse_timeout(" 3 s "), Ok(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn rejects_invalid_timeouts() {
+        assert!(parse_timeout("").is_err());
+        assert!(parse_timeout("5").is_err());
+        assert!(parse_timeout("s").is_err());
+        assert!(parse_timeout("5 days").is_err());
+        assert!(parse_timeout("-5s").is_err());
+        assert!(parse_timeout("18446744073709551615h").is_err());
+        assert_eq!(parse_timeout("18446744073709551615s"), Ok(Duration::from_secs(u64::max_value())));
+    }
+
+    #[test]
+    fn parses_timeout_tags() {
+        assert_eq!(parse_timeout_tag("@timeout(10s)"), Some(Ok(Duration::from_secs(10))));
+        assert!(parse_timeout_tag("@timeout(10)").unwrap().is_err());
+        assert_eq!(parse_timeout_tag("@timeout"), None);
+        assert_eq!(parse_timeout_tag("@slow"), None);
+    }
+}
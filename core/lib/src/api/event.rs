@@ -4,7 +4,8 @@ use std::time::SystemTime;
 use gherkin::ast::{Feature, Background, Scenario};
 use gherkin::cuke::Cuke;
 
-use crate::api::{TestCase, TestStep, TestResult};
+use crate::api::{CukeStepTestStep, HookTestStep, StepDefinition, TestCase, TestStep, TestResult};
+use crate::error::Error;
 
 /// An execution event that is generated by cucumber.
 #[derive(Debug)]
@@ -19,15 +20,67 @@ pub enum Event<'e, 's> {
         feature: &'e Feature,
         cukes: &'e [Cuke<'e>],
     },
+    /// Sent for each feature file that could not be read or parsed.
+    ///
+    /// The other feature files are still run, but the test run fails.
+    TestSourceParseError {
+        time: SystemTime,
+        uri: &'e str,
+        /// The line of the parse error, if it is known.
+        line: Option<u32>,
+        /// The column of the parse error, if it is known.
+        column: Option<u32>,
+        error: &'e Error,
+    },
+    /// Sent with all step definitions of the glue right before `TestRunStarted`.
+    StepDefinitionsRead {
+        time: SystemTime,
+        step_definitions: &'e [&'e dyn StepDefinition],
+    },
     /// Sent before any execution of test steps is started,
     /// but after all feature files have been parsed.
     TestRunStarted {
         time: SystemTime,
         num_cukes: usize,
     },
+    /// Sent before the execution of a global `BeforeAll` or `AfterAll` hook.
+    ///
+    /// `BeforeAll` hooks are run right after `TestRunStarted`,
+    /// `AfterAll` hooks right before `TestRunFinished`.
+    GlobalHookStarted {
+        time: SystemTime,
+        hook: &'e dyn HookTestStep<'s>,
+    },
+    /// Sent after the execution of a global `BeforeAll` or `AfterAll` hook.
+    GlobalHookFinished {
+        time: SystemTime,
+        hook: &'e dyn HookTestStep<'s>,
+        result: &'e TestResult,
+    },
+    /// Sent before the execution of a `BeforeFeature` or `AfterFeature` hook.
+    ///
+    /// `BeforeFeature` hooks are run before the first test case of a feature is started,
+    /// `AfterFeature` hooks after the last test case of a feature is finished.
+    FeatureHookStarted {
+        time: SystemTime,
+        uri: &'e str,
+        feature: &'e Feature,
+        hook: &'e dyn HookTestStep<'s>,
+    },
+    /// Sent after the execution of a `BeforeFeature` or `AfterFeature` hook.
+    FeatureHookFinished {
+        time: SystemTime,
+        uri: &'e str,
+        feature: &'e Feature,
+        hook: &'e dyn HookTestStep<'s>,
+        result: &'e TestResult,
+    },
     /// Sent before starting the execution of a test case.
+    ///
+    /// A failed test case that is retried is started again with an incremented `attempt`.
     TestCaseStarted {
         time: SystemTime,
+        attempt: u32,
         uri: &'e str,
         feature: &'e Feature,
         feature_background: Option<&'e Background>,
@@ -69,6 +122,16 @@ pub enum Event<'e, 's> {
         test_step: &'e TestStep<'e, 's>,
         result: &'e TestResult,
     },
+    /// Sent after an undefined test step with a step definition snippet that would match it.
+    SnippetSuggested {
+        time: SystemTime,
+        uri: &'e str,
+        test_case: &'e dyn TestCase,
+        test_step: &'e dyn CukeStepTestStep<'s>,
+        /// The Rust source code of the step definition like
+        /// `#[given("^I have (\\d+) cukes$")] pub fn i_have_cukes(arg1: &str) { ... }`.
+        snippet: &'e str,
+    },
     /// Sent after the execution of a test step.
     TestCaseFinished {
         time: SystemTime,
@@ -79,6 +142,10 @@ pub enum Event<'e, 's> {
         scenario: &'e Scenario,
         test_case: &'e dyn TestCase,
         result: &'e TestResult,
+        attempt: u32,
+        /// Whether the failed test case will be retried,
+        /// so that its result is not the final one.
+        will_be_retried: bool,
     },
     /// Sent after all executions of test steps are finished.
     ///
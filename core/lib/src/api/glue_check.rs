@@ -0,0 +1,91 @@
+use std::fmt;
+
+use crate::error::StepDefinitionCandidate;
+
+/// The report of `cuke_runner::check_glue` about step definitions that match the same steps.
+#[derive(Debug, Clone)]
+pub struct GlueCheck {
+    /// The groups of overlapping step definitions, in the order in which their first step was found.
+    pub overlaps: Vec<StepDefinitionOverlap>,
+}
+
+/// Step definitions whose patterns match the same steps of the feature files.
+#[derive(Debug, Clone)]
+pub struct StepDefinitionOverlap {
+    /// The overlapping step definitions, sorted by their location.
+    pub candidates: Vec<StepDefinitionCandidate>,
+    /// The steps that match all candidates, like `calc.feature:12 When I press 1`.
+    pub steps: Vec<String>,
+}
+
+impl GlueCheck {
+    /// Whether every step matches at most one step definition.
+    pub fn is_ok(&self) -> bool {
+        self.overlaps.is_empty()
+    }
+
+    /// Adds a step that matched more than one step definition
+    /// to the overlap of these step definitions.
+    pub(crate) fn add_ambiguous_step(&mut self, candidates: Vec<StepDefinitionCandidate>, step: String) {
+        let overlap = self.overlaps.iter_mut()
+            .find(|overlap| overlap.candidates.iter().map(StepDefinitionCandidate::pattern)
+                .eq(candidates.iter().map(StepDefinitionCandidate::pattern)));
+
+        match overlap {
+            Some(overlap) => overlap.steps.push(step),
+            None => self.overlaps.push(StepDefinitionOverlap {
+                candidates,
+                steps: vec![step],
+            }),
+        }
+    }
+}
+
+impl fmt::Display for GlueCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.overlaps.is_empty() {
+            return f.write_str("no overlapping step definitions");
+        }
+
+        write!(f, "{} overlapping step definition groups:", self.overlaps.len())?;
+        for overlap in &self.overlaps {
+            writeln!(f)?;
+            for candidate in &overlap.candidates {
+                write!(f, "\n  {} `{}`", candidate.location(), candidate.pattern())?;
+            }
+            for step in &overlap.steps {
+                write!(f, "\n    matches {}", step)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::candidate;
+
+    use super::*;
+
+    #[test]
+    fn groups_steps_by_overlapping_step_definitions() {
+        let mut glue_check = GlueCheck { overlaps: Vec::new() };
+        assert!(glue_check.is_ok());
+
+        let press = || vec![candidate("steps/a.rs", 12, "I press (.+)"), candidate("steps/b.rs", 40, "I press (\\d+)")];
+        glue_check.add_ambiguous_step(press(), "calc.feature:5 When I press 1".to_owned());
+        glue_check.add_ambiguous_step(
+            vec![candidate("steps/a.rs", 20, "the result is (.*)"), candidate("steps/b.rs", 50, "the result is (\\d+)")],
+            "calc.feature:6 Then the result is 1".to_owned());
+        glue_check.add_ambiguous_step(press(), "calc.feature:9 When I press 2".to_owned());
+
+        assert!(!glue_check.is_ok());
+        assert_eq!(glue_check.overlaps.len(), 2);
+        assert_eq!(glue_check.overlaps[0].steps, vec![
+            "calc.feature:5 When I press 1".to_owned(),
+            "calc.feature:9 When I press 2".to_owned(),
+        ]);
+        assert_eq!(glue_check.overlaps[1].steps, vec!["calc.feature:6 Then the result is 1".to_owned()]);
+    }
+}
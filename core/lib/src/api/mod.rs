@@ -1,11 +1,17 @@
 pub use self::feature_file::FeatureFile;
+pub use self::glue_check::{GlueCheck, StepDefinitionOverlap};
 pub use self::location::GlueCodeLocation;
+pub use self::run_outcome::{FailedScenario, RunOutcome};
+pub use self::step_definition::StepDefinition;
 pub use self::test_case::TestCase;
 pub use self::test_result::{TestResult, TestResultStatus};
 pub use self::test_step::{CukeStepTestStep, HookTestStep, HookType, TestStep};
 
 mod feature_file;
+mod glue_check;
 mod location;
+mod run_outcome;
+mod step_definition;
 mod test_step;
 mod test_case;
 mod test_result;
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::api::TestResultStatus;
+
+/// The outcome of a test run,
+/// so that callers can decide themselves what to print and how to exit.
+#[derive(Debug, Clone)]
+pub struct RunOutcome {
+    /// The number of scenarios per final status (after retries).
+    pub status_counts: BTreeMap<TestResultStatus, usize>,
+    /// The scenarios that failed the run, in the order in which they finished.
+    pub failed_scenarios: Vec<FailedScenario>,
+    pub duration: Duration,
+    /// Whether the run passed with regard to `Config::strict` and `Config::fail_on_flaky`.
+    ///
+    /// The run also fails if a global or feature hook failed.
+    pub success: bool,
+}
+
+/// A scenario that failed the run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailedScenario {
+    pub uri: String,
+    /// The line of the example row for scenario outlines, otherwise the line of the scenario.
+    pub line: u32,
+    pub name: String,
+    pub status: TestResultStatus,
+    pub error_message: Option<String>,
+}
+
+impl RunOutcome {
+    /// The number of scenarios that finished with the given status.
+    pub fn count(&self, status: TestResultStatus) -> usize {
+        self.status_counts.get(&status).copied().unwrap_or(0)
+    }
+
+    /// The number of scenarios that were run.
+    pub fn scenario_count(&self) -> usize {
+        self.status_counts.values().sum()
+    }
+
+    /// The process exit status for this outcome: `0` on success, otherwise `1`.
+    pub fn exit_status(&self) -> i32 {
+        if self.success { 0 } else { 1 }
+    }
+}
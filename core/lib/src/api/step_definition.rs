@@ -0,0 +1,22 @@
+use std::fmt::Debug;
+
+use crate::api::GlueCodeLocation;
+use crate::glue::step::StepKeyword;
+
+/// A step definition of the glue code (a `#[step(...)]` annotated function).
+pub trait StepDefinition: Debug + Send + Sync {
+    /// The pattern or expression used to match the step definition to Gherkin steps.
+    fn get_pattern(&self) -> &str;
+
+    /// The name of the step definition function.
+    fn get_name(&self) -> &str;
+
+    /// The keyword of the step definition attribute like `Given` or `*` for `#[step]`.
+    fn get_keyword(&self) -> StepKeyword;
+
+    /// Representation of the source code location of the step definition function.
+    fn get_location(&self) -> &GlueCodeLocation;
+
+    /// The type names of the step definition function parameters that take step arguments.
+    fn get_parameter_types(&self) -> &[&str];
+}
@@ -16,5 +16,8 @@ pub trait TestCase: Debug + Send + Sync {
 
     fn get_line(&self) -> u32;
 
+    /// The line of the example row for scenario outlines, otherwise the line of the scenario.
+    fn get_example_line(&self) -> u32;
+
     fn get_tags(&self) -> &[Tag<'_>];
 }
@@ -9,6 +9,8 @@ use crate::error::Error;
 pub enum TestResultStatus {
     Passed,
     Skipped,
+    /// A test case that failed at first, but passed when it was retried.
+    Flaky,
     Pending,
     Undefined,
     Ambiguous,
@@ -21,7 +23,9 @@ impl TestResultStatus {
     }
 
     fn has_always_ok_status(self) -> bool {
-        self.eq(&TestResultStatus::Passed) || self.eq(&TestResultStatus::Skipped)
+        self.eq(&TestResultStatus::Passed)
+            || self.eq(&TestResultStatus::Skipped)
+            || self.eq(&TestResultStatus::Flaky)
     }
 
     fn has_ok_when_not_strict_status(self) -> bool {
@@ -34,6 +38,7 @@ impl TestResultStatus {
         match self {
             Passed => 32 /* green */,
             Skipped => 36 /* cyan */,
+            Flaky => 33 /* yellow */,
             Pending => 33 /* yellow */,
             Undefined => 33 /* yellow */,
             Ambiguous => 31 /* red */,
@@ -49,6 +54,7 @@ impl fmt::Display for TestResultStatus {
         match *self {
             Passed => write!(f, "Passed"),
             Skipped => write!(f, "Skipped"),
+            Flaky => write!(f, "Flaky"),
             Pending => write!(f, "Pending"),
             Undefined => write!(f, "Undefined"),
             Ambiguous => write!(f, "Ambiguous"),
@@ -79,14 +85,21 @@ impl TestResult {
             let mut error_message = String::new();
 
             let fail = error.as_fail();
-            error_message.push_str(&format!("{}", fail));
+            let mut previous_message = format!("{}", fail);
+            error_message.push_str(&previous_message);
 
             if let Some(backtrace) = error.backtrace() {
                 error_message.push_str(&format!("\n{}", backtrace));
             }
 
             for cause in fail.iter_causes() {
-                error_message.push_str(&format!("\ncaused by: {}", cause));
+                // wrapping errors display the message of their cause,
+                // which would otherwise be repeated for every wrapper
+                let message = format!("{}", cause);
+                if message != previous_message {
+                    error_message.push_str(&format!("\ncaused by: {}", message));
+                    previous_message = message;
+                }
 
                 if let Some(backtrace) = cause.backtrace() {
                     error_message.push_str(&format!("\n{}", backtrace));
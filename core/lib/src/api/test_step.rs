@@ -3,6 +3,7 @@ use std::fmt::Debug;
 use gherkin::cuke;
 
 use crate::api::GlueCodeLocation;
+use crate::glue::step::StepKeyword;
 use crate::glue::step::argument::StepArgument;
 
 /// A test step can either represent the execution of a hook or a cuke step.
@@ -15,13 +16,18 @@ pub enum TestStep<'a, 's> {
 
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
 pub enum HookType {
+    BeforeAll,
+    BeforeFeature,
     BeforeScenario,
     AfterScenario,
     BeforeStep,
     AfterStep,
+    AfterFeature,
+    AfterAll,
 }
 
-/// Hooks are invoked before and after each scenario and before and after each gherkin step in a scenario.
+/// Hooks are invoked once before and after the whole test run, before and after each feature,
+/// before and after each scenario and before and after each gherkin step in a scenario.
 pub trait HookTestStep<'s>: Debug + Send + Sync {
     /// Representation of the source code location of the glue.
     fn get_glue_code_location(&self) -> Option<&GlueCodeLocation>;
@@ -38,6 +44,17 @@ pub trait CukeStepTestStep<'s>: Debug + Send + Sync {
     /// The pattern or expression used to match the glue code to the Gherkin step.
     fn get_pattern(&self) -> Option<&str>;
 
+    /// The name of the matched step definition function.
+    fn get_step_definition_name(&self) -> Option<&str>;
+
+    /// The keyword of the matched step definition attribute like `Given` or `*` for `#[step]`.
+    fn get_step_definition_keyword(&self) -> Option<StepKeyword>;
+
+    /// The type names of the step definition function parameters that take step arguments.
+    ///
+    /// Empty if the step is not matched by exactly one step definition.
+    fn get_parameter_types(&self) -> &[&str];
+
     /// The matched Gherkin step.
     fn get_cuke_step(&self) -> &cuke::Step<'_>;
 
@@ -0,0 +1,499 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::api::event::{EventListener, SyncEventListener};
+use crate::config::{
+    Config, Error as ConfigError, ExecutionMode, ExecutionModeKind, Profile, SyncEventListenerRef,
+};
+use crate::error::{Error, Result};
+use crate::glue::EmbeddedFeatureFile;
+use crate::glue::filter::line::LinePredicate;
+use crate::glue::filter::name::NamePredicate;
+use crate::glue::filter::tag::TagPredicate;
+
+/// Builds an `OwnedConfig`, see `Config::builder`.
+///
+/// ```rust,ignore
+/// let config = Config::builder()
+///     .features_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/features"))
+///     .strict(true)
+///     .tag("not @ignore")
+///     .execution_mode(ExecutionModeKind::Sequential)
+///     .event_listener(PrettyPrintListener::new())
+///     .build()?;
+///
+/// let run_outcome = config.with_config(|config| cuke_runner::run(glue, config))?;
+/// ```
+#[derive(Debug)]
+pub struct ConfigBuilder {
+    features_dir: Option<PathBuf>,
+    feature_paths: Vec<PathBuf>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    embedded_features: Option<&'static [EmbeddedFeatureFile]>,
+    output_dir: PathBuf,
+    strict: bool,
+    colored_output: bool,
+    dry_run: bool,
+    tags: Vec<String>,
+    names: Vec<String>,
+    lines: Vec<String>,
+    rerun_file: Option<PathBuf>,
+    step_timeout: Option<Duration>,
+    retries: u32,
+    fail_on_flaky: bool,
+    threads: Option<usize>,
+    execution_mode: ExecutionModeKind,
+    event_listeners: Vec<Box<dyn SyncEventListener>>,
+    sequential_event_listeners: Vec<Box<dyn EventListener>>,
+}
+
+/// A `Config` that owns its values, as built by the `ConfigBuilder`.
+///
+/// Its tag expressions, name patterns and line selectors are already validated.
+#[derive(Debug)]
+pub struct OwnedConfig {
+    features_dir: PathBuf,
+    feature_paths: Vec<PathBuf>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    embedded_features: Option<&'static [EmbeddedFeatureFile]>,
+    output_dir: PathBuf,
+    strict: bool,
+    colored_output: bool,
+    dry_run: bool,
+    tags: Vec<TagPredicate<'static>>,
+    names: Vec<String>,
+    lines: Vec<String>,
+    rerun_file: Option<PathBuf>,
+    step_timeout: Option<Duration>,
+    retries: u32,
+    fail_on_flaky: bool,
+    threads: Option<usize>,
+    execution_mode: ExecutionModeKind,
+    event_listeners: Vec<Box<dyn SyncEventListener>>,
+    sequential_event_listeners: Vec<Box<dyn EventListener>>,
+}
+
+impl<'c> Config<'c> {
+    /// Creates a builder for a config that owns its values,
+    /// e.g. to build it from environment variables or files, or to return it from a function.
+    ///
+    /// Only the features directory is required, unless embedded features are run.
+    /// The output directory defaults to `target/cucumber`, the execution mode to `ParallelScenarios`,
+    /// colored output and failing on flaky scenarios are enabled and everything else is disabled.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+
+    /// Creates a builder for a config with the values of the named profile of a `cukes.toml` config file.
+    ///
+    /// Fails if the config file or the profile does not exist or if the profile is malformed.
+    pub fn from_file<P: AsRef<Path>>(path: P, profile_name: &str) -> Result<ConfigBuilder> {
+        let profile = Profile::from_file(path.as_ref(), profile_name)?;
+        Ok(ConfigBuilder::new().profile(profile))
+    }
+
+    /// Creates a builder for a config with the values of the profile that is selected
+    /// by the `CUKE_PROFILE` environment variable, see `Profile::load`.
+    ///
+    /// Without a selected profile, the builder has the same defaults as `Config::builder`.
+    pub fn load() -> Result<ConfigBuilder> {
+        let profile = Profile::load()?.unwrap_or_default();
+        Ok(ConfigBuilder::new().profile(profile))
+    }
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        ConfigBuilder::new()
+    }
+}
+
+impl ConfigBuilder {
+    pub fn new() -> ConfigBuilder {
+        ConfigBuilder {
+            features_dir: None,
+            feature_paths: Vec::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            embedded_features: None,
+            output_dir: PathBuf::from("target/cucumber"),
+            strict: false,
+            colored_output: true,
+            dry_run: false,
+            tags: Vec::new(),
+            names: Vec::new(),
+            lines: Vec::new(),
+            rerun_file: None,
+            step_timeout: None,
+            retries: 0,
+            fail_on_flaky: true,
+            threads: None,
+            execution_mode: ExecutionModeKind::ParallelScenarios,
+            event_listeners: Vec::new(),
+            sequential_event_listeners: Vec::new(),
+        }
+    }
+
+    /// Sets the values that are defined by the profile.
+    pub fn profile(mut self, profile: Profile) -> Self {
+        if let Some(features_dir) = profile.features_dir {
+            self.features_dir = Some(features_dir);
+        }
+        if let Some(feature_paths) = profile.feature_paths {
+            self.feature_paths.extend(feature_paths);
+        }
+        if let Some(include) = profile.include {
+            self.include.extend(include);
+        }
+        if let Some(exclude) = profile.exclude {
+            self.exclude.extend(exclude);
+        }
+        if let Some(output_dir) = profile.output_dir {
+            self.output_dir = output_dir;
+        }
+        if let Some(tags) = profile.tags {
+            self.tags.extend(tags);
+        }
+        if let Some(strict) = profile.strict {
+            self.strict = strict;
+        }
+        if let Some(dry_run) = profile.dry_run {
+            self.dry_run = dry_run;
+        }
+        if let Some(execution_mode) = profile.execution_mode {
+            self.execution_mode = execution_mode;
+        }
+        if let Some(threads) = profile.threads {
+            self.threads = Some(threads);
+        }
+        self
+    }
+
+    pub fn features_dir<P: Into<PathBuf>>(mut self, features_dir: P) -> Self {
+        self.features_dir = Some(features_dir.into());
+        self
+    }
+
+    /// Adds another feature directory or an individual feature file.
+    pub fn feature_path<P: Into<PathBuf>>(mut self, feature_path: P) -> Self {
+        self.feature_paths.push(feature_path.into());
+        self
+    }
+
+    /// Adds a glob pattern of which the uri of a feature file must match at least one.
+    pub fn include<S: Into<String>>(mut self, glob: S) -> Self {
+        self.include.push(glob.into());
+        self
+    }
+
+    /// Adds a glob pattern of the uris of feature files that are skipped.
+    pub fn exclude<S: Into<String>>(mut self, glob: S) -> Self {
+        self.exclude.push(glob.into());
+        self
+    }
+
+    /// Runs the feature files of `embed_features!` instead of reading them at runtime.
+    pub fn embedded_features(mut self, embedded_features: &'static [EmbeddedFeatureFile]) -> Self {
+        self.embedded_features = Some(embedded_features);
+        self
+    }
+
+    pub fn output_dir<P: Into<PathBuf>>(mut self, output_dir: P) -> Self {
+        self.output_dir = output_dir.into();
+        self
+    }
+
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    pub fn colored_output(mut self, colored_output: bool) -> Self {
+        self.colored_output = colored_output;
+        self
+    }
+
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Adds a tag expression that the scenarios must match, like `not @slow`.
+    pub fn tag<S: Into<String>>(mut self, tag_expression: S) -> Self {
+        self.tags.push(tag_expression.into());
+        self
+    }
+
+    /// Adds a regex of which the scenario names must match at least one.
+    pub fn name<S: Into<String>>(mut self, name_pattern: S) -> Self {
+        self.names.push(name_pattern.into());
+        self
+    }
+
+    /// Adds a `path/to.feature:12` selector, see `Config::lines`.
+    pub fn line<S: Into<String>>(mut self, line_selector: S) -> Self {
+        self.lines.push(line_selector.into());
+        self
+    }
+
+    pub fn rerun_file<P: Into<PathBuf>>(mut self, rerun_file: P) -> Self {
+        self.rerun_file = Some(rerun_file.into());
+        self
+    }
+
+    pub fn step_timeout(mut self, step_timeout: Duration) -> Self {
+        self.step_timeout = Some(step_timeout);
+        self
+    }
+
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn fail_on_flaky(mut self, fail_on_flaky: bool) -> Self {
+        self.fail_on_flaky = fail_on_flaky;
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    pub fn execution_mode(mut self, execution_mode: ExecutionModeKind) -> Self {
+        self.execution_mode = execution_mode;
+        self
+    }
+
+    /// Adds an event listener that can be used with every execution mode.
+    pub fn event_listener<L: SyncEventListener + 'static>(mut self, event_listener: L) -> Self {
+        self.event_listeners.push(Box::new(event_listener));
+        self
+    }
+
+    /// Adds an event listener that is not `Sync`, which requires the `Sequential` execution mode.
+    ///
+    /// It is notified after the listeners of `event_listener`.
+    pub fn sequential_event_listener<L: EventListener + 'static>(mut self, event_listener: L) -> Self {
+        self.sequential_event_listeners.push(Box::new(event_listener));
+        self
+    }
+
+    /// Validates the values and builds the config.
+    pub fn build(self) -> Result<OwnedConfig> {
+        let features_dir = match (self.features_dir, self.embedded_features) {
+            (Some(features_dir), _) => features_dir,
+            (None, Some(_)) => PathBuf::new(),
+            (None, None) => return Err(Error::from(ConfigError::MissingValue("features_dir".to_owned()))),
+        };
+
+        let tags = self.tags.into_iter()
+            .map(TagPredicate::from_string)
+            .collect::<std::result::Result<Vec<TagPredicate<'static>>, String>>()
+            .map_err(Error::InvalidFilter)?;
+
+        if !self.names.is_empty() {
+            NamePredicate::new(&self.names.iter().map(String::as_str).collect::<Vec<&str>>())
+                .map_err(Error::InvalidFilter)?;
+        }
+        if !self.lines.is_empty() {
+            LinePredicate::parse(&self.lines.iter().map(String::as_str).collect::<Vec<&str>>())
+                .map_err(Error::InvalidFilter)?;
+        }
+
+        if self.threads == Some(0) {
+            return Err(Error::from(ConfigError::InvalidValue {
+                name: "threads".to_owned(),
+                value: "0".to_owned(),
+                message: "must be at least 1".to_owned(),
+            }));
+        }
+
+        if self.execution_mode != ExecutionModeKind::Sequential && !self.sequential_event_listeners.is_empty() {
+            return Err(Error::from(ConfigError::IncompatibleExecutionMode(format!(
+                "execution mode \"{:?}\" requires event listeners that are Sync, \
+                but sequential event listeners were added", self.execution_mode))));
+        }
+
+        Ok(OwnedConfig {
+            features_dir,
+            feature_paths: self.feature_paths,
+            include: self.include,
+            exclude: self.exclude,
+            embedded_features: self.embedded_features,
+            output_dir: self.output_dir,
+            strict: self.strict,
+            colored_output: self.colored_output,
+            dry_run: self.dry_run,
+            tags,
+            names: self.names,
+            lines: self.lines,
+            rerun_file: self.rerun_file,
+            step_timeout: self.step_timeout,
+            retries: self.retries,
+            fail_on_flaky: self.fail_on_flaky,
+            threads: self.threads,
+            execution_mode: self.execution_mode,
+            event_listeners: self.event_listeners,
+            sequential_event_listeners: self.sequential_event_listeners,
+        })
+    }
+}
+
+impl OwnedConfig {
+    /// Calls `f` with a `Config` that borrows the values of this config,
+    /// e.g. `owned_config.with_config(|config| cuke_runner::run(glue, config))`.
+    pub fn with_config<F, R>(&self, f: F) -> R
+        where F: FnOnce(Config<'_>) -> R
+    {
+        let names = self.names.iter().map(String::as_str).collect::<Vec<&str>>();
+        let lines = self.lines.iter().map(String::as_str).collect::<Vec<&str>>();
+        let feature_paths = self.feature_paths.iter().map(PathBuf::as_path).collect::<Vec<&Path>>();
+        let include = self.include.iter().map(String::as_str).collect::<Vec<&str>>();
+        let exclude = self.exclude.iter().map(String::as_str).collect::<Vec<&str>>();
+
+        let sync_event_listeners = self.event_listeners.iter()
+            .map(|event_listener| event_listener.as_ref())
+            .collect::<Vec<&dyn SyncEventListener>>();
+        let sync_event_listener_refs = sync_event_listeners.iter()
+            .map(|event_listener| SyncEventListenerRef(*event_listener))
+            .collect::<Vec<SyncEventListenerRef<'_>>>();
+        let sequential_event_listeners = sync_event_listener_refs.iter()
+            .map(|event_listener| event_listener as &dyn EventListener)
+            .chain(self.sequential_event_listeners.iter()
+                .map(|event_listener| event_listener.as_ref() as &dyn EventListener))
+            .collect::<Vec<&dyn EventListener>>();
+
+        let execution_mode = match self.execution_mode {
+            ExecutionModeKind::ParallelScenarios => ExecutionMode::ParallelScenarios {
+                event_listeners: sync_event_listeners.as_slice(),
+            },
+            ExecutionModeKind::ParallelFeatures => ExecutionMode::ParallelFeatures {
+                event_listeners: sync_event_listeners.as_slice(),
+            },
+            ExecutionModeKind::Sequential => ExecutionMode::Sequential {
+                event_listeners: sequential_event_listeners.as_slice(),
+            },
+        };
+
+        f(Config {
+            features_dir: self.features_dir.as_path(),
+            feature_paths: feature_paths.as_slice(),
+            include: include.as_slice(),
+            exclude: exclude.as_slice(),
+            embedded_features: self.embedded_features,
+            output_dir: self.output_dir.as_path(),
+            strict: self.strict,
+            colored_output: self.colored_output,
+            dry_run: self.dry_run,
+            tags: &[],
+            tag_predicates: self.tags.as_slice(),
+            names: names.as_slice(),
+            lines: lines.as_slice(),
+            rerun_file: self.rerun_file.as_deref(),
+            step_timeout: self.step_timeout,
+            retries: self.retries,
+            fail_on_flaky: self.fail_on_flaky,
+            threads: self.threads,
+            execution_mode,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag_expressions<'c>(config: &'c Config<'_>) -> Vec<&'c str> {
+        config.tag_predicates.iter()
+            .map(TagPredicate::as_str)
+            .collect()
+    }
+
+    #[test]
+    fn builds_config_with_defaults() {
+        let config = Config::builder()
+            .features_dir("tests/features")
+            .tag("not @slow")
+            .build()
+            .unwrap();
+
+        config.with_config(|config| {
+            assert_eq!(config.features_dir, PathBuf::from("tests/features"));
+            assert_eq!(config.output_dir, PathBuf::from("target/cucumber"));
+            assert_eq!(tag_expressions(&config), vec!["not @slow"]);
+            assert!(config.fail_on_flaky);
+            match config.execution_mode {
+                ExecutionMode::ParallelScenarios { event_listeners } => assert!(event_listeners.is_empty()),
+                execution_mode => panic!("unexpected execution mode: {:?}", execution_mode),
+            }
+        });
+    }
+
+    #[test]
+    fn builds_config_from_profile() {
+        let profile = Profile {
+            features_dir: Some(PathBuf::from("/crate/tests/features")),
+            feature_paths: Some(vec![PathBuf::from("/crate/smoke.feature")]),
+            exclude: Some(vec!["**/wip_*.feature".to_owned()]),
+            tags: Some(vec!["not @wip".to_owned()]),
+            strict: Some(true),
+            execution_mode: Some(ExecutionModeKind::Sequential),
+            ..Profile::default()
+        };
+
+        let config = Config::builder()
+            .tag("not @slow")
+            .profile(profile)
+            .build()
+            .unwrap();
+
+        config.with_config(|config| {
+            assert_eq!(config.features_dir, PathBuf::from("/crate/tests/features"));
+            assert_eq!(config.feature_paths, &[Path::new("/crate/smoke.feature")]);
+            assert_eq!(config.exclude, &["**/wip_*.feature"]);
+            assert_eq!(tag_expressions(&config), vec!["not @slow", "not @wip"]);
+            assert!(config.strict);
+            assert!(!config.dry_run);
+            match config.execution_mode {
+                ExecutionMode::Sequential { .. } => {}
+                execution_mode => panic!("unexpected execution mode: {:?}", execution_mode),
+            }
+        });
+    }
+
+    #[test]
+    fn missing_config_file_is_an_error() {
+        match Config::from_file("does/not/exist/cukes.toml", "default") {
+            Err(Error::Config(ConfigError::NotFound)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        fn build_error(builder: ConfigBuilder) -> Error {
+            builder.build().unwrap_err()
+        }
+
+        match build_error(Config::builder()) {
+            Error::Config(ConfigError::MissingValue(ref name)) if name == "features_dir" => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+        match build_error(Config::builder().features_dir("features").tag("(@a")) {
+            Error::InvalidFilter(_) => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+        match build_error(Config::builder().features_dir("features").line("to.feature:x")) {
+            Error::InvalidFilter(_) => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+        match build_error(Config::builder().features_dir("features").threads(0)) {
+            Error::Config(ConfigError::InvalidValue { .. }) => {}
+            err => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
@@ -13,6 +13,20 @@ pub enum Error {
     EnvVar(#[cause] env::VarError, &'static str),
     /// The configuration file was not found.
     NotFound,
+    /// The configuration file is malformed.
+    Toml(#[cause] toml::de::Error),
+    /// The selected profile is not defined in the configuration file.
+    ProfileNotFound(String),
+    /// The execution mode of a profile cannot be used with the configured event listeners.
+    IncompatibleExecutionMode(String),
+    /// An environment variable or command line argument has an invalid value.
+    InvalidValue {
+        name: String,
+        value: String,
+        message: String,
+    },
+    /// A command line argument or required config value is missing.
+    MissingValue(String),
     /// Hints that destructuring should not be exhaustive.
     ///
     /// This enum may grow additional variants, so this makes sure clients
@@ -28,6 +42,12 @@ impl From<io::Error> for Error {
     }
 }
 
+impl From<toml::de::Error> for Error {
+    fn from(err: toml::de::Error) -> Error {
+        Error::Toml(err)
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
@@ -35,6 +55,12 @@ impl fmt::Display for Error {
             Error::EnvVar(ref err, ref var) =>
                 write!(f, "required env variable \"{}\" could not be used: {}", var, err),
             Error::NotFound => write!(f, "config file was not found"),
+            Error::Toml(ref err) => write!(f, "config file is malformed: {}", err),
+            Error::ProfileNotFound(ref name) => write!(f, "profile \"{}\" is not defined in the config file", name),
+            Error::IncompatibleExecutionMode(ref message) => write!(f, "{}", message),
+            Error::InvalidValue { ref name, ref value, ref message } =>
+                write!(f, "invalid value \"{}\" for \"{}\": {}", value, name, message),
+            Error::MissingValue(ref name) => write!(f, "missing value for \"{}\"", name),
             Error::__Nonexhaustive => unreachable!(),
         }
     }
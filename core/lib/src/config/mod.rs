@@ -1,19 +1,63 @@
 use std::path::Path;
 use std::default::Default;
+use std::time::Duration;
 
+pub use self::builder::{ConfigBuilder, OwnedConfig};
 pub use self::error::Error;
-use crate::api::event::{EventListener, SyncEventListener};
+pub(crate) use self::overrides::{override_arg_count, Overrides};
+pub use self::profile::{ExecutionModeKind, Profile};
+use crate::api::event::{Event, EventListener, SyncEventListener};
+use crate::glue::EmbeddedFeatureFile;
+use crate::glue::filter::tag::TagPredicate;
 
+mod builder;
 mod error;
+mod overrides;
+mod profile;
 
 #[derive(Debug)]
 pub struct Config<'c> {
     pub features_dir: &'c Path,
+    /// Additional feature directories or individual feature files, e.g. shared features of another crate.
+    ///
+    /// The uris of their feature files are relative to the current directory if possible,
+    /// and full paths if they would be the same as the uri of another feature file.
+    pub feature_paths: &'c [&'c Path],
+    /// Glob patterns of which the uri of a feature file must match at least one (e.g. `billing/**`).
+    /// All feature files are included if there are no patterns.
+    pub include: &'c [&'c str],
+    /// Glob patterns of the uris of feature files that are skipped (e.g. `wip/**`).
+    pub exclude: &'c [&'c str],
+    /// Runs the feature files that were embedded into the test binary by `embed_features!`
+    /// instead of reading the features directory and feature paths at runtime.
+    pub embedded_features: Option<&'c [EmbeddedFeatureFile]>,
     pub output_dir: &'c Path,
     pub strict: bool,
     pub colored_output: bool,
     pub dry_run: bool,
     pub tags: &'c [&'c str],
+    /// Tag expressions that were already parsed, like the tags of a config built with `Config::builder()`.
+    ///
+    /// Scenarios must match them in addition to the `tags`.
+    pub tag_predicates: &'c [TagPredicate<'c>],
+    /// Runs only the scenarios whose name matches at least one of these regexes.
+    pub names: &'c [&'c str],
+    /// Runs only the scenarios at these `path/to.feature:12:30` selectors.
+    ///
+    /// Paths are relative to the features directory, but may also start with the features directory
+    /// (e.g. `tests/features/to.feature:12` or an absolute path), as copied from an editor.
+    pub lines: &'c [&'c str],
+    /// Runs only the scenarios listed in this rerun file,
+    /// like the `rerun.txt` that is written to the output directory after each run.
+    pub rerun_file: Option<&'c Path>,
+    /// The timeout of steps and hooks that do not declare their own `timeout`.
+    pub step_timeout: Option<Duration>,
+    /// How often a failed scenario is retried, unless its `@retry(...)` tag says otherwise.
+    pub retries: u32,
+    /// Whether scenarios that only passed when they were retried fail the test run.
+    pub fail_on_flaky: bool,
+    /// The number of threads of the parallel execution modes, defaults to the number of CPUs.
+    pub threads: Option<usize>,
     pub execution_mode: ExecutionMode<'c>,
 }
 
@@ -41,3 +85,13 @@ impl<'c> Default for ExecutionMode<'c> {
         }
     }
 }
+
+/// Makes the event listeners of a parallel execution mode usable in the `Sequential` execution mode.
+#[derive(Debug)]
+pub(crate) struct SyncEventListenerRef<'a>(pub &'a dyn SyncEventListener);
+
+impl<'a> EventListener for SyncEventListenerRef<'a> {
+    fn on_event(&self, event: &Event<'_, '_>) {
+        self.0.on_event(event)
+    }
+}
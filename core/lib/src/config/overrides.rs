@@ -0,0 +1,492 @@
+use std::env::{self, VarError};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::api::event::EventListener;
+use crate::config::{Config, Error, ExecutionMode, ExecutionModeKind, Profile, SyncEventListenerRef};
+use crate::glue::timeout;
+
+/// Config values from the `cukes.toml` profile, environment variables and command line arguments
+/// that override the values of the `Config` in the test source code.
+///
+/// Command line arguments take precedence over environment variables,
+/// which take precedence over the profile:
+///
+/// | Environment variable  | Argument                        |
+/// |-----------------------|---------------------------------|
+/// | `CUKE_FEATURES`       | `--features <dir>`              |
+/// | `CUKE_FEATURE_PATHS`  | `--feature-path <path>`         |
+/// | `CUKE_INCLUDE`        | `--include <glob>`              |
+/// | `CUKE_EXCLUDE`        | `--exclude <glob>`              |
+/// | `CUKE_OUTPUT_DIR`     | `--output-dir <dir>`            |
+/// | `CUKE_TAGS`           | `--tags <expression>`, `-t`     |
+/// | `CUKE_NAME`           | `--name <regex>`, `-n`          |
+/// | `CUKE_LINES`          | `path/to.feature:12`            |
+/// | `CUKE_RERUN_FILE`     | `--rerun-file <file>`           |
+/// | `CUKE_STRICT`         | `--strict`, `--no-strict`       |
+/// | `CUKE_DRY_RUN`        | `--dry-run`, `--no-dry-run`     |
+/// | `CUKE_COLORED_OUTPUT` | `--monochrome`                  |
+/// | `CUKE_RETRIES`        | `--retries <count>`             |
+/// | `CUKE_FAIL_ON_FLAKY`  | `--fail-on-flaky`               |
+/// | `CUKE_STEP_TIMEOUT`   | `--step-timeout <timeout>`      |
+/// | `CUKE_THREADS`        | `--threads <count>`             |
+///
+/// Arguments that are repeated (and `CUKE_LINES`, `CUKE_INCLUDE` and `CUKE_EXCLUDE` separated by whitespace,
+/// `CUKE_FEATURE_PATHS` separated like `PATH`) add up.
+/// Unknown arguments are ignored, because they may be meant for the test harness.
+/// Note that the default libtest harness rejects unknown options,
+/// so the arguments can only be used with `harness = false` test targets,
+/// like the ones of the `cuke_runner::main!` macro.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct Overrides {
+    features_dir: Option<PathBuf>,
+    feature_paths: Vec<PathBuf>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    output_dir: Option<PathBuf>,
+    tags: Vec<String>,
+    names: Vec<String>,
+    lines: Vec<String>,
+    rerun_file: Option<PathBuf>,
+    strict: Option<bool>,
+    dry_run: Option<bool>,
+    colored_output: Option<bool>,
+    retries: Option<u32>,
+    fail_on_flaky: Option<bool>,
+    step_timeout: Option<Duration>,
+    execution_mode: Option<ExecutionModeKind>,
+    threads: Option<usize>,
+}
+
+impl From<Profile> for Overrides {
+    fn from(profile: Profile) -> Self {
+        Overrides {
+            features_dir: profile.features_dir,
+            feature_paths: profile.feature_paths.unwrap_or_default(),
+            include: profile.include.unwrap_or_default(),
+            exclude: profile.exclude.unwrap_or_default(),
+            output_dir: profile.output_dir,
+            tags: profile.tags.unwrap_or_default(),
+            strict: profile.strict,
+            dry_run: profile.dry_run,
+            execution_mode: profile.execution_mode,
+            threads: profile.threads,
+            ..Overrides::default()
+        }
+    }
+}
+
+impl Overrides {
+    /// Loads the overrides from the selected profile
+    /// and the environment variables and command line arguments of this process.
+    pub fn load() -> Result<Overrides, Error> {
+        let mut overrides = Profile::load()?
+            .map(Overrides::from)
+            .unwrap_or_default();
+
+        let mut env_var_overrides = Overrides::default();
+        env_var_overrides.read_env_vars(|name| env::var(name))?;
+        overrides.merge(env_var_overrides);
+
+        let mut arg_overrides = Overrides::default();
+        arg_overrides.read_args(env::args().skip(1))?;
+        overrides.merge(arg_overrides);
+
+        Ok(overrides)
+    }
+
+    /// Replaces the values of these overrides with the values that are set in `other`.
+    fn merge(&mut self, other: Overrides) {
+        fn replace_vec<T>(value: &mut Vec<T>, other: Vec<T>) {
+            if !other.is_empty() {
+                *value = other;
+            }
+        }
+
+        self.features_dir = other.features_dir.or_else(|| self.features_dir.take());
+        replace_vec(&mut self.feature_paths, other.feature_paths);
+        replace_vec(&mut self.include, other.include);
+        replace_vec(&mut self.exclude, other.exclude);
+        self.output_dir = other.output_dir.or_else(|| self.output_dir.take());
+        replace_vec(&mut self.tags, other.tags);
+        replace_vec(&mut self.names, other.names);
+        replace_vec(&mut self.lines, other.lines);
+        self.rerun_file = other.rerun_file.or_else(|| self.rerun_file.take());
+        self.strict = other.strict.or(self.strict);
+        self.dry_run = other.dry_run.or(self.dry_run);
+        self.colored_output = other.colored_output.or(self.colored_output);
+        self.retries = other.retries.or(self.retries);
+        self.fail_on_flaky = other.fail_on_flaky.or(self.fail_on_flaky);
+        self.step_timeout = other.step_timeout.or(self.step_timeout);
+        self.execution_mode = other.execution_mode.or(self.execution_mode);
+        self.threads = other.threads.or(self.threads);
+    }
+
+    fn read_env_vars<F>(&mut self, var: F) -> Result<(), Error>
+        where F: Fn(&'static str) -> Result<String, VarError>
+    {
+        let value = |name: &'static str| match var(name) {
+            Ok(value) => Ok(Some(value)),
+            Err(VarError::NotPresent) => Ok(None),
+            Err(err) => Err(Error::EnvVar(err, name)),
+        };
+
+        if let Some(features_dir) = value("CUKE_FEATURES")? {
+            self.features_dir = Some(PathBuf::from(features_dir));
+        }
+        if let Some(feature_paths) = value("CUKE_FEATURE_PATHS")? {
+            self.feature_paths.extend(env::split_paths(&feature_paths));
+        }
+        if let Some(include) = value("CUKE_INCLUDE")? {
+            self.include.extend(include.split_whitespace().map(str::to_owned));
+        }
+        if let Some(exclude) = value("CUKE_EXCLUDE")? {
+            self.exclude.extend(exclude.split_whitespace().map(str::to_owned));
+        }
+        if let Some(output_dir) = value("CUKE_OUTPUT_DIR")? {
+            self.output_dir = Some(PathBuf::from(output_dir));
+        }
+        if let Some(tags) = value("CUKE_TAGS")? {
+            self.tags.push(tags);
+        }
+        if let Some(name) = value("CUKE_NAME")? {
+            self.names.push(name);
+        }
+        if let Some(lines) = value("CUKE_LINES")? {
+            self.lines.extend(lines.split_whitespace().map(str::to_owned));
+        }
+        if let Some(rerun_file) = value("CUKE_RERUN_FILE")? {
+            self.rerun_file = Some(PathBuf::from(rerun_file));
+        }
+        if let Some(strict) = value("CUKE_STRICT")? {
+            self.strict = Some(parse_bool("CUKE_STRICT", &strict)?);
+        }
+        if let Some(dry_run) = value("CUKE_DRY_RUN")? {
+            self.dry_run = Some(parse_bool("CUKE_DRY_RUN", &dry_run)?);
+        }
+        if let Some(colored_output) = value("CUKE_COLORED_OUTPUT")? {
+            self.colored_output = Some(parse_bool("CUKE_COLORED_OUTPUT", &colored_output)?);
+        }
+        if let Some(retries) = value("CUKE_RETRIES")? {
+            self.retries = Some(parse_number("CUKE_RETRIES", &retries)?);
+        }
+        if let Some(fail_on_flaky) = value("CUKE_FAIL_ON_FLAKY")? {
+            self.fail_on_flaky = Some(parse_bool("CUKE_FAIL_ON_FLAKY", &fail_on_flaky)?);
+        }
+        if let Some(step_timeout) = value("CUKE_STEP_TIMEOUT")? {
+            self.step_timeout = Some(parse_timeout("CUKE_STEP_TIMEOUT", &step_timeout)?);
+        }
+        if let Some(threads) = value("CUKE_THREADS")? {
+            self.threads = Some(parse_number("CUKE_THREADS", &threads)?);
+        }
+
+        Ok(())
+    }
+
+    fn read_args<I>(&mut self, args: I) -> Result<(), Error>
+        where I: IntoIterator<Item = String>
+    {
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.find('=') {
+                Some(index) if arg.starts_with("--") => (&arg[..index], Some(arg[index + 1..].to_owned())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || match inline_value.clone() {
+                Some(value) => Ok(value),
+                None => args.next().ok_or_else(|| Error::MissingValue(name.to_owned())),
+            };
+
+            match name {
+                "--features" => self.features_dir = Some(PathBuf::from(value()?)),
+                "--feature-path" => self.feature_paths.push(PathBuf::from(value()?)),
+                "--include" => self.include.push(value()?),
+                "--exclude" => self.exclude.push(value()?),
+                "--output-dir" => self.output_dir = Some(PathBuf::from(value()?)),
+                "--tags" | "-t" => self.tags.push(value()?),
+                "--name" | "-n" => self.names.push(value()?),
+                "--rerun-file" => self.rerun_file = Some(PathBuf::from(value()?)),
+                "--strict" => self.strict = Some(true),
+                "--no-strict" => self.strict = Some(false),
+                "--dry-run" => self.dry_run = Some(true),
+                "--no-dry-run" => self.dry_run = Some(false),
+                "--monochrome" => self.colored_output = Some(false),
+                "--retries" => self.retries = Some(parse_number(name, &value()?)?),
+                "--fail-on-flaky" => self.fail_on_flaky = Some(true),
+                "--step-timeout" => self.step_timeout = Some(parse_timeout(name, &value()?)?),
+                "--threads" => self.threads = Some(parse_number(name, &value()?)?),
+                line_selector if !line_selector.starts_with('-') && is_line_selector(line_selector) => {
+                    self.lines.push(line_selector.to_owned());
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calls `f` with the config after the overrides were applied to it.
+    ///
+    /// Fails if the execution mode is changed from `Sequential` to a parallel execution mode
+    /// while there are event listeners, because these may not be `Sync`.
+    pub fn apply<F, R>(&self, config: Config<'_>, f: F) -> Result<R, Error>
+        where F: FnOnce(Config<'_>) -> R
+    {
+        let feature_paths = self.feature_paths.iter().map(PathBuf::as_path).collect::<Vec<&Path>>();
+        let include = self.include.iter().map(String::as_str).collect::<Vec<&str>>();
+        let exclude = self.exclude.iter().map(String::as_str).collect::<Vec<&str>>();
+        let tags = self.tags.iter().map(String::as_str).collect::<Vec<&str>>();
+        let names = self.names.iter().map(String::as_str).collect::<Vec<&str>>();
+        let lines = self.lines.iter().map(String::as_str).collect::<Vec<&str>>();
+
+        let sync_event_listeners = match config.execution_mode {
+            ExecutionMode::ParallelScenarios { event_listeners } |
+            ExecutionMode::ParallelFeatures { event_listeners } => event_listeners.iter()
+                .map(|event_listener| SyncEventListenerRef(*event_listener))
+                .collect::<Vec<SyncEventListenerRef<'_>>>(),
+            ExecutionMode::Sequential { .. } => Vec::new(),
+        };
+        let sequential_event_listeners = sync_event_listeners.iter()
+            .map(|event_listener| event_listener as &dyn EventListener)
+            .collect::<Vec<&dyn EventListener>>();
+
+        let mut config: Config<'_> = config;
+        if let Some(execution_mode) = self.execution_mode {
+            config.execution_mode = match (execution_mode, config.execution_mode) {
+                (ExecutionModeKind::Sequential, ExecutionMode::Sequential { event_listeners }) =>
+                    ExecutionMode::Sequential { event_listeners },
+                (ExecutionModeKind::Sequential, _) =>
+                    ExecutionMode::Sequential { event_listeners: &sequential_event_listeners },
+                (_, ExecutionMode::Sequential { event_listeners }) if !event_listeners.is_empty() => {
+                    return Err(Error::IncompatibleExecutionMode(format!(
+                        "execution mode \"{:?}\" requires event listeners that are Sync, \
+                        but the event listeners were configured for the Sequential execution mode",
+                        execution_mode)));
+                }
+                (ExecutionModeKind::ParallelScenarios, ExecutionMode::ParallelScenarios { event_listeners }) |
+                (ExecutionModeKind::ParallelScenarios, ExecutionMode::ParallelFeatures { event_listeners }) =>
+                    ExecutionMode::ParallelScenarios { event_listeners },
+                (ExecutionModeKind::ParallelFeatures, ExecutionMode::ParallelScenarios { event_listeners }) |
+                (ExecutionModeKind::ParallelFeatures, ExecutionMode::ParallelFeatures { event_listeners }) =>
+                    ExecutionMode::ParallelFeatures { event_listeners },
+                (ExecutionModeKind::ParallelScenarios, ExecutionMode::Sequential { .. }) =>
+                    ExecutionMode::ParallelScenarios { event_listeners: &[] },
+                (ExecutionModeKind::ParallelFeatures, ExecutionMode::Sequential { .. }) =>
+                    ExecutionMode::ParallelFeatures { event_listeners: &[] },
+            };
+        }
+        if let Some(ref features_dir) = self.features_dir {
+            config.features_dir = features_dir.as_path();
+        }
+        if !feature_paths.is_empty() {
+            config.feature_paths = feature_paths.as_slice();
+        }
+        if !include.is_empty() {
+            config.include = include.as_slice();
+        }
+        if !exclude.is_empty() {
+            config.exclude = exclude.as_slice();
+        }
+        if let Some(ref output_dir) = self.output_dir {
+            config.output_dir = output_dir.as_path();
+        }
+        if !tags.is_empty() {
+            config.tags = tags.as_slice();
+            config.tag_predicates = &[];
+        }
+        if !names.is_empty() {
+            config.names = names.as_slice();
+        }
+        if !lines.is_empty() {
+            config.lines = lines.as_slice();
+        }
+        if let Some(ref rerun_file) = self.rerun_file {
+            config.rerun_file = Some(rerun_file.as_path());
+        }
+        config.strict = self.strict.unwrap_or(config.strict);
+        config.dry_run = self.dry_run.unwrap_or(config.dry_run);
+        config.colored_output = self.colored_output.unwrap_or(config.colored_output);
+        config.retries = self.retries.unwrap_or(config.retries);
+        config.fail_on_flaky = self.fail_on_flaky.unwrap_or(config.fail_on_flaky);
+        config.step_timeout = self.step_timeout.or(config.step_timeout);
+        config.threads = self.threads.or(config.threads);
+
+        Ok(f(config))
+    }
+}
+
+/// The arguments of the overrides that are followed by a value.
+const VALUE_ARGS: &[&str] = &[
+    "--features", "--feature-path", "--include", "--exclude", "--output-dir", "--tags", "-t", "--name", "-n",
+    "--rerun-file", "--retries", "--step-timeout", "--threads",
+];
+
+/// The arguments of the overrides that are flags.
+const FLAG_ARGS: &[&str] = &[
+    "--strict", "--no-strict", "--dry-run", "--no-dry-run", "--monochrome", "--fail-on-flaky",
+];
+
+/// Returns how many arguments (starting with `arg`) belong to the overrides,
+/// so that other argument parsers can skip them.
+pub(crate) fn override_arg_count(arg: &str) -> usize {
+    let name = match arg.find('=') {
+        Some(index) if arg.starts_with("--") => &arg[..index],
+        _ => arg,
+    };
+
+    if VALUE_ARGS.contains(&name) {
+        if name.len() == arg.len() { 2 } else { 1 }
+    } else if FLAG_ARGS.contains(&name) || (!arg.starts_with('-') && is_line_selector(arg)) {
+        1
+    } else {
+        0
+    }
+}
+
+/// Whether the argument selects lines of a feature file, like `a/b.feature:12` or `a/b.feature:12:34`.
+fn is_line_selector(arg: &str) -> bool {
+    let mut path = arg;
+    let mut has_lines = false;
+
+    while let Some(index) = path.rfind(':') {
+        let line = &path[index + 1..];
+
+        if line.is_empty() || !line.bytes().all(|byte| byte.is_ascii_digit()) {
+            break;
+        }
+
+        has_lines = true;
+        path = &path[..index];
+    }
+
+    has_lines && Path::new(path).extension().map_or(false, |extension| extension == "feature")
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool, Error> {
+    match value.trim() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(invalid_value(name, value, "expected \"true\" or \"false\"".to_owned())),
+    }
+}
+
+fn parse_number<T: FromStr>(name: &str, value: &str) -> Result<T, Error>
+    where T::Err: ToString
+{
+    value.trim().parse::<T>()
+        .map_err(|err| invalid_value(name, value, err.to_string()))
+}
+
+fn parse_timeout(name: &str, value: &str) -> Result<Duration, Error> {
+    timeout::parse_timeout(value)
+        .map_err(|message| invalid_value(name, value, message))
+}
+
+fn invalid_value(name: &str, value: &str, message: String) -> Error {
+    Error::InvalidValue {
+        name: name.to_owned(),
+        value: value.to_owned(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Overrides, Error> {
+        let mut overrides = Overrides::default();
+        overrides.read_args(args.iter().map(|arg| (*arg).to_owned()))?;
+        Ok(overrides)
+    }
+
+    fn env_vars(vars: &'static [(&'static str, &'static str)]) -> Result<Overrides, Error> {
+        let mut overrides = Overrides::default();
+        overrides.read_env_vars(|name| vars.iter()
+            .find(|(var_name, _)| *var_name == name)
+            .map(|(_, value)| (*value).to_owned())
+            .ok_or(VarError::NotPresent))?;
+        Ok(overrides)
+    }
+
+    #[test]
+    fn reads_env_vars() {
+        let overrides = env_vars(&[
+            ("CUKE_TAGS", "@fast and not @slow"),
+            ("CUKE_DRY_RUN", "true"),
+            ("CUKE_STRICT", "0"),
+            ("CUKE_LINES", "a.feature:3 b.feature:4:8"),
+            ("CUKE_STEP_TIMEOUT", "5s"),
+            ("CUKE_EXCLUDE", "**/wip_*.feature **/slow_*.feature"),
+        ]).unwrap();
+
+        assert_eq!(overrides.tags, vec!["@fast and not @slow"]);
+        assert_eq!(overrides.dry_run, Some(true));
+        assert_eq!(overrides.strict, Some(false));
+        assert_eq!(overrides.lines, vec!["a.feature:3", "b.feature:4:8"]);
+        assert_eq!(overrides.step_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(overrides.exclude, vec!["**/wip_*.feature", "**/slow_*.feature"]);
+        assert_eq!(overrides.features_dir, None);
+    }
+
+    #[test]
+    fn rejects_invalid_env_var_values() {
+        assert!(env_vars(&[("CUKE_DRY_RUN", "maybe")]).is_err());
+        assert!(env_vars(&[("CUKE_RETRIES", "-1")]).is_err());
+        assert!(env_vars(&[("CUKE_STEP_TIMEOUT", "5")]).is_err());
+    }
+
+    #[test]
+    fn reads_args() {
+        let overrides = args(&[
+            "--tags", "@fast", "-t", "not @slow", "--name", "^Add", "--dry-run",
+            "--retries=2", "features/a.feature:12", "--nocapture", "some_test_filter",
+            "--feature-path", "smoke.feature", "--include=calc/**",
+        ]).unwrap();
+
+        assert_eq!(overrides.tags, vec!["@fast", "not @slow"]);
+        assert_eq!(overrides.names, vec!["^Add"]);
+        assert_eq!(overrides.dry_run, Some(true));
+        assert_eq!(overrides.retries, Some(2));
+        assert_eq!(overrides.lines, vec!["features/a.feature:12"]);
+        assert_eq!(overrides.feature_paths, vec![PathBuf::from("smoke.feature")]);
+        assert_eq!(overrides.include, vec!["calc/**"]);
+    }
+
+    #[test]
+    fn later_overrides_replace_earlier_ones() {
+        let mut overrides = Overrides::from(Profile {
+            tags: Some(vec!["@profile".to_owned()]),
+            strict: Some(true),
+            threads: Some(2),
+            ..Profile::default()
+        });
+        overrides.merge(args(&["--tags", "@arg", "--threads", "8"]).unwrap());
+
+        assert_eq!(overrides.tags, vec!["@arg"]);
+        assert_eq!(overrides.strict, Some(true));
+        assert_eq!(overrides.threads, Some(8));
+    }
+
+    #[test]
+    fn counts_override_args() {
+        assert_eq!(override_arg_count("--tags"), 2);
+        assert_eq!(override_arg_count("--tags=@fast"), 1);
+        assert_eq!(override_arg_count("--strict"), 1);
+        assert_eq!(override_arg_count("a/b.feature:12"), 1);
+        assert_eq!(override_arg_count("a/b.feature:12:34"), 1);
+        assert_eq!(override_arg_count("a/b.feature"), 0);
+        assert_eq!(override_arg_count("a/b.feature:"), 0);
+        assert_eq!(override_arg_count("calc.feature::Add two numbers (line 4)"), 0);
+        assert_eq!(override_arg_count("--exact"), 0);
+        assert_eq!(override_arg_count("scenario"), 0);
+    }
+
+    #[test]
+    fn rejects_args_without_value() {
+        assert!(args(&["--tags"]).is_err());
+        assert!(args(&["--retries", "many"]).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::env::{self, VarError};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::config::Error;
+
+/// The name of the config file next to the `Cargo.toml` of the crate with the cucumber tests.
+pub const CONFIG_FILE_NAME: &str = "cukes.toml";
+
+/// The profile that is used if the `CUKE_PROFILE` environment variable is not set.
+pub const DEFAULT_PROFILE_NAME: &str = "default";
+
+/// A named run profile of the `cukes.toml` config file.
+///
+/// ```toml
+/// [profiles.default]
+/// tags = ["not @slow"]
+///
+/// [profiles.ci]
+/// features_dir = "tests/features"
+/// feature_paths = ["tests/smoke.feature"]
+/// include = ["calc/**"]
+/// exclude = ["**/wip_*.feature"]
+/// output_dir = "target/cucumber"
+/// tags = ["not @wip"]
+/// strict = true
+/// execution_mode = "parallel_scenarios"
+/// threads = 4
+/// ```
+///
+/// Every value is optional and overrides the corresponding `Config` value.
+/// Relative paths are relative to the directory of the config file.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub features_dir: Option<PathBuf>,
+    pub feature_paths: Option<Vec<PathBuf>>,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub output_dir: Option<PathBuf>,
+    pub tags: Option<Vec<String>>,
+    pub strict: Option<bool>,
+    pub dry_run: Option<bool>,
+    pub execution_mode: Option<ExecutionModeKind>,
+    pub threads: Option<usize>,
+}
+
+/// The kind of an `ExecutionMode` without its event listeners.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionModeKind {
+    ParallelScenarios,
+    ParallelFeatures,
+    Sequential,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl Profile {
+    /// Reads the profile with the given name from a config file.
+    pub fn from_file(path: &Path, name: &str) -> Result<Profile, Error> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Err(Error::NotFound),
+            Err(err) => return Err(Error::Io(err)),
+        };
+
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+        parse_profile(&content, name, base_dir)
+    }
+
+    /// Loads the profile of the `CUKE_PROFILE` environment variable
+    /// from the `cukes.toml` next to the `Cargo.toml` of the tested crate.
+    ///
+    /// Without `CUKE_PROFILE`, the `default` profile is used if there is a config file that defines it,
+    /// otherwise `None` is returned.
+    pub fn load() -> Result<Option<Profile>, Error> {
+        let manifest_dir = env::var("CARGO_MANIFEST_DIR")
+            .map_err(|err| Error::EnvVar(err, "CARGO_MANIFEST_DIR"))?;
+        let path = Path::new(&manifest_dir).join(CONFIG_FILE_NAME);
+
+        match env::var("CUKE_PROFILE") {
+            Ok(name) => Profile::from_file(&path, &name).map(Some),
+            Err(VarError::NotPresent) => match Profile::from_file(&path, DEFAULT_PROFILE_NAME) {
+                Ok(profile) => Ok(Some(profile)),
+                Err(Error::NotFound) | Err(Error::ProfileNotFound(_)) => Ok(None),
+                Err(err) => Err(err),
+            },
+            Err(err) => Err(Error::EnvVar(err, "CUKE_PROFILE")),
+        }
+    }
+}
+
+fn parse_profile(content: &str, name: &str, base_dir: &Path) -> Result<Profile, Error> {
+    let mut config_file = toml::from_str::<ConfigFile>(content)?;
+
+    let mut profile = config_file.profiles.remove(name)
+        .ok_or_else(|| Error::ProfileNotFound(name.to_owned()))?;
+    profile.features_dir = profile.features_dir.map(|path| base_dir.join(path));
+    profile.feature_paths = profile.feature_paths.map(|paths| paths.into_iter()
+        .map(|path| base_dir.join(path))
+        .collect());
+    profile.output_dir = profile.output_dir.map(|path| base_dir.join(path));
+
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG_FILE: &str = r#"
+        [profiles.default]
+        tags = ["not @slow"]
+
+        [profiles.ci]
+        features_dir = "tests/features"
+        feature_paths = ["smoke.feature"]
+        exclude = ["**/wip_*.feature"]
+        strict = true
+        execution_mode = "parallel_features"
+        threads = 4
+    "#;
+
+    #[test]
+    fn parses_profile() {
+        let profile = parse_profile(CONFIG_FILE, "ci", Path::new("/crate")).unwrap();
+
+        assert_eq!(profile, Profile {
+            features_dir: Some(PathBuf::from("/crate/tests/features")),
+            feature_paths: Some(vec![PathBuf::from("/crate/smoke.feature")]),
+            exclude: Some(vec!["**/wip_*.feature".to_owned()]),
+            strict: Some(true),
+            execution_mode: Some(ExecutionModeKind::ParallelFeatures),
+            threads: Some(4),
+            ..Profile::default()
+        });
+    }
+
+    #[test]
+    fn missing_profile_is_an_error() {
+        match parse_profile(CONFIG_FILE, "local", Path::new("")) {
+            Err(Error::ProfileNotFound(ref name)) if name == "local" => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn malformed_profile_is_an_error() {
+        let config_file = "[profiles.ci]\nexecution_mode = \"fast\"\n";
+
+        match parse_profile(config_file, "ci", Path::new("")) {
+            Err(Error::Toml(_)) => {}
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use std::fmt;
 use std::result;
 use std::io;
+use std::time::Duration;
 
 use gherkin;
 use walkdir;
 use failure::Fail;
 
+use crate::api::GlueCodeLocation;
 use crate::config;
 use crate::glue;
 
@@ -15,7 +17,7 @@ pub type Result<T> = result::Result<T, Error>;
 /// The specific type of an error.
 #[derive(Fail, Debug)]
 pub enum Error {
-    /// An io error that occurred while reading feature files.
+    /// An io error that occurred while reading feature files or reading or writing the rerun file.
     Io(#[cause] io::Error),
     /// An error that occurred while reading the cukes config.
     Config(#[cause] config::Error),
@@ -26,8 +28,31 @@ pub enum Error {
     /// An error that occurred while converting scenario data to a step function parameter.
     FromScenario(#[cause] glue::scenario::FromScenarioError),
     /// An error that occurred while executing a step or hook function.
-    Execution(crate::glue::error::ExecutionError),
-    AmbiguousStepDefinitions,
+    Execution(#[cause] crate::glue::error::ExecutionError),
+    /// A step or hook took longer than its timeout.
+    Timeout {
+        step: String,
+        elapsed: Duration,
+        timeout: Duration,
+    },
+    /// An `AfterFeature` or `AfterAll` hook could not be run,
+    /// because its data is still used by a step that exceeded its timeout.
+    HookDataInUse,
+    /// A tag expression, name pattern or line selector of the config is invalid.
+    InvalidFilter(String),
+    /// A tag with an argument like `@timeout(...)` of a scenario is invalid.
+    InvalidTag(String),
+    /// A feature file that could not be read or parsed
+    /// when listing or checking the cukes of a config.
+    InvalidFeatureFile {
+        uri: String,
+        message: String,
+    },
+    /// A step matched more than one step definition.
+    AmbiguousStepDefinitions {
+        step: String,
+        candidates: Vec<StepDefinitionCandidate>,
+    },
     UndefinedStepDefinition,
     Pending,
     /// Hints that destructuring should not be exhaustive.
@@ -39,6 +64,37 @@ pub enum Error {
     __Nonexhaustive,
 }
 
+/// One of the step definitions that matched an ambiguous step.
+#[derive(Debug, Clone)]
+pub struct StepDefinitionCandidate {
+    pub(crate) pattern: String,
+    pub(crate) location: GlueCodeLocation,
+}
+
+impl StepDefinitionCandidate {
+    /// Returns the regex of the step definition.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the location of the step definition.
+    pub fn location(&self) -> &GlueCodeLocation {
+        &self.location
+    }
+}
+
+/// Creates a candidate of the step definition at the line of the file.
+#[cfg(test)]
+pub(crate) fn candidate(file: &str, line_number: u32, pattern: &str) -> StepDefinitionCandidate {
+    StepDefinitionCandidate {
+        pattern: pattern.to_owned(),
+        location: GlueCodeLocation {
+            file_path: std::path::PathBuf::from(file),
+            line_number,
+        },
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(err: io::Error) -> Error {
         Error::Io(err)
@@ -84,10 +140,45 @@ impl fmt::Display for Error {
             Error::Gherkin(ref err) => write!(f, "{}", err),
             Error::FromScenario(ref err) => write!(f, "{}", err),
             Error::Execution(ref err) => write!(f, "{}", err),
-            Error::AmbiguousStepDefinitions => write!(f, "AmbiguousStepDefinitions"),
+            Error::Timeout { ref step, elapsed, timeout } =>
+                write!(f, "{} timed out after {:?} (timeout: {:?})", step, elapsed, timeout),
+            Error::HookDataInUse => write!(f, "hook data is still used by a step that timed out"),
+            Error::InvalidFilter(ref message) => write!(f, "invalid filter: {}", message),
+            Error::InvalidTag(ref message) => write!(f, "invalid tag: {}", message),
+            Error::InvalidFeatureFile { ref uri, ref message } =>
+                write!(f, "invalid feature file \"{}\": {}", uri, message),
+            Error::AmbiguousStepDefinitions { ref step, ref candidates } => {
+                write!(f, "step \"{}\" matched by: ", step)?;
+                for (index, candidate) in candidates.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{} `{}`", candidate.location, candidate.pattern)?;
+                }
+                Ok(())
+            }
             Error::UndefinedStepDefinition => write!(f, "UndefinedStepDefinition"),
             Error::Pending => write!(f, "TODO: implement me"),
             Error::__Nonexhaustive => unreachable!(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_ambiguous_candidates() {
+        let error = Error::AmbiguousStepDefinitions {
+            step: "Given I press 1".to_owned(),
+            candidates: vec![
+                candidate("steps/a.rs", 12, "I press (.+)"),
+                candidate("steps/b.rs", 40, "I press (\\d+)"),
+            ],
+        };
+
+        assert_eq!(error.to_string(),
+            "step \"Given I press 1\" matched by: steps/a.rs:12 `I press (.+)`, steps/b.rs:40 `I press (\\d+)`");
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt;
+
+use crate::config::override_arg_count;
+
+/// The output format of the harness, like `--format` of libtest.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum Format {
+    Pretty,
+    Terse,
+    Json,
+}
+
+/// Which tests are run with regard to their `@ignore` tag.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum RunIgnored {
+    No,
+    Only,
+    Yes,
+}
+
+/// The libtest command line arguments that are supported by the harness.
+///
+/// The arguments of the config overrides (e.g. `--tags @fast`) are skipped.
+#[derive(Debug, PartialEq)]
+pub(crate) struct Args {
+    pub filters: Vec<String>,
+    pub skip: Vec<String>,
+    pub exact: bool,
+    pub list: bool,
+    pub run_ignored: RunIgnored,
+    pub format: Format,
+    pub test_threads: Option<usize>,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            filters: Vec::new(),
+            skip: Vec::new(),
+            exact: false,
+            list: false,
+            run_ignored: RunIgnored::No,
+            format: Format::Pretty,
+            test_threads: None,
+        }
+    }
+}
+
+/// An invalid libtest command line argument.
+#[derive(Debug, PartialEq)]
+pub(crate) struct ArgsError(String);
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Args {
+    pub fn parse<I>(args: I) -> Result<Args, ArgsError>
+        where I: IntoIterator<Item = String>
+    {
+        let args = args.into_iter().collect::<Vec<String>>();
+        let mut parsed_args = Args::default();
+        let mut index = 0;
+
+        while index < args.len() {
+            let arg = &args[index];
+
+            let override_arg_count = override_arg_count(arg);
+            if override_arg_count > 0 {
+                index += override_arg_count;
+                continue;
+            }
+
+            let (name, inline_value) = match arg.find('=') {
+                Some(index) if arg.starts_with("--") => (&arg[..index], Some(&arg[index + 1..])),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || match inline_value {
+                Some(value) => Ok(value.to_owned()),
+                None => {
+                    index += 1;
+                    args.get(index)
+                        .cloned()
+                        .ok_or_else(|| ArgsError(format!("argument \"{}\" requires a value", name)))
+                }
+            };
+
+            match name {
+                "--exact" => parsed_args.exact = true,
+                "--list" => parsed_args.list = true,
+                "--ignored" => parsed_args.run_ignored = RunIgnored::Only,
+                "--include-ignored" => parsed_args.run_ignored = RunIgnored::Yes,
+                "--skip" => parsed_args.skip.push(value()?),
+                "-q" | "--quiet" => parsed_args.format = Format::Terse,
+                "--format" => parsed_args.format = match value()?.as_str() {
+                    "pretty" => Format::Pretty,
+                    "terse" => Format::Terse,
+                    "json" => Format::Json,
+                    format => return Err(ArgsError(format!(
+                        "argument \"--format\" must be one of pretty, terse or json, but was \"{}\"", format))),
+                },
+                "--test-threads" => {
+                    let test_threads = value()?;
+                    match test_threads.parse::<usize>() {
+                        Ok(test_threads) if test_threads > 0 => parsed_args.test_threads = Some(test_threads),
+                        _ => return Err(ArgsError(format!(
+                            "argument \"--test-threads\" must be a positive number, but was \"{}\"", test_threads))),
+                    }
+                }
+                // accepted for compatibility with cargo and libtest, but without effect
+                "--nocapture" | "--show-output" | "--test" | "--bench" => {}
+                "--color" | "--logfile" | "-Z" => {
+                    value()?;
+                }
+                filter if !filter.starts_with('-') => parsed_args.filters.push(filter.to_owned()),
+                unknown => return Err(ArgsError(format!("unrecognized argument \"{}\"", unknown))),
+            }
+
+            index += 1;
+        }
+
+        Ok(parsed_args)
+    }
+
+    /// Whether the test with the given name is selected by the filters and `--skip`.
+    pub fn is_selected(&self, test_name: &str) -> bool {
+        let matches = |filter: &String| if self.exact {
+            test_name == filter
+        } else {
+            test_name.contains(filter.as_str())
+        };
+
+        (self.filters.is_empty() || self.filters.iter().any(matches))
+            && !self.skip.iter().any(matches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, ArgsError> {
+        Args::parse(args.iter().map(|arg| (*arg).to_owned()))
+    }
+
+    #[test]
+    fn parses_libtest_args() {
+        let args = parse(&["Add", "--exact", "--skip", "slow", "--format=json", "--test-threads", "2",
+            "--include-ignored", "--nocapture"]).unwrap();
+
+        assert_eq!(args, Args {
+            filters: vec!["Add".to_owned()],
+            skip: vec!["slow".to_owned()],
+            exact: true,
+            run_ignored: RunIgnored::Yes,
+            format: Format::Json,
+            test_threads: Some(2),
+            ..Args::default()
+        });
+    }
+
+    #[test]
+    fn skips_override_args() {
+        let args = parse(&["--tags", "@fast", "--strict", "--name=Add", "calc.feature:12", "-q"]).unwrap();
+
+        assert_eq!(args, Args {
+            format: Format::Terse,
+            ..Args::default()
+        });
+    }
+
+    #[test]
+    fn rejects_invalid_args() {
+        assert!(parse(&["--unknown"]).is_err());
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["--test-threads", "0"]).is_err());
+        assert!(parse(&["--skip"]).is_err());
+    }
+
+    #[test]
+    fn keeps_test_names_as_filters() {
+        let args = parse(&["calc.feature::Add two numbers (line 4)", "calc.feature:9"]).unwrap();
+
+        assert_eq!(args.filters, vec!["calc.feature::Add two numbers (line 4)".to_owned()]);
+    }
+
+    #[test]
+    fn selects_tests_by_filters() {
+        let args = parse(&["Add", "--skip", "slow"]).unwrap();
+
+        assert!(args.is_selected("calc.feature::Add two numbers (line 4)"));
+        assert!(!args.is_selected("calc.feature::Subtract two numbers (line 9)"));
+        assert!(!args.is_selected("calc.feature::Add slow numbers (line 14)"));
+
+        let args = parse(&["--exact", "calc.feature::Add two numbers (line 4)"]).unwrap();
+
+        assert!(args.is_selected("calc.feature::Add two numbers (line 4)"));
+        assert!(!args.is_selected("calc.feature::Add two numbers (line 40)"));
+    }
+}
@@ -0,0 +1,171 @@
+//! A libtest compatible test harness that runs every cuke as its own test.
+//!
+//! The harness is used by `harness = false` test targets with the `cuke_runner::main!` macro:
+//!
+//! ```toml
+//! [[test]]
+//! name = "cukes"
+//! harness = false
+//! ```
+//!
+//! Tests are named `path/to.feature::Scenario name (line 12)`,
+//! where the line is the line of the example row for scenario outlines.
+//! Scenarios tagged with `@ignore` are ignored tests.
+//!
+//! The libtest arguments `[FILTER]`, `--exact`, `--skip`, `--list`, `--ignored`, `--include-ignored`,
+//! `--format pretty|terse|json`, `--quiet` and `--test-threads` are supported
+//! next to the arguments that override the config (e.g. `--tags`).
+
+use std::env;
+use std::process;
+use std::time::Instant;
+
+use crate::Config;
+use crate::config::Overrides;
+use crate::runtime::{self, Glue};
+
+use self::args::{Args, RunIgnored};
+use self::output::{HarnessListener, Output, Summary};
+
+mod args;
+mod output;
+
+/// Defines the `main` function of a `harness = false` test target
+/// that runs the cukes as libtest compatible tests.
+///
+/// ```rust,ignore
+/// use cuke_runner::{Config, Glue};
+///
+/// mod steps;
+///
+/// cuke_runner::main!(cuke_runner::glue![steps], Config {
+///     features_dir: ...,
+///     ...
+/// });
+/// ```
+#[macro_export]
+macro_rules! main {
+    ($glue:expr, $config:expr $(,)?) => {
+        fn main() {
+            $crate::harness::main($glue, $config)
+        }
+    };
+}
+
+/// Runs the cukes as libtest compatible tests and exits the process with the libtest exit code.
+pub fn main(glue: Glue, config: Config<'_>) -> ! {
+    let exit_code = match run(glue, config) {
+        Ok(exit_code) => exit_code,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            101
+        }
+    };
+
+    process::exit(exit_code)
+}
+
+/// The tag of scenarios that are ignored tests.
+const IGNORE_TAG: &str = "@ignore";
+
+/// The name of the test of a cuke.
+pub(crate) fn test_name(uri: &str, name: &str, line: u32) -> String {
+    format!("{}::{} (line {})", uri, name, line)
+}
+
+#[derive(Debug)]
+struct Test {
+    name: String,
+    /// The `uri:line` selector that runs exactly this test.
+    selector: String,
+    ignored: bool,
+}
+
+fn run(glue: Glue, config: Config<'_>) -> Result<i32, String> {
+    let args = Args::parse(env::args().skip(1))
+        .map_err(|err| err.to_string())?;
+    let overrides = Overrides::load()
+        .map_err(|err| format!("cucumber tests could not be configured: {}", err))?;
+
+    overrides.apply(config, |config| run_tests(glue, config, &args))
+        .map_err(|err| format!("cucumber tests could not be configured: {}", err))?
+}
+
+fn run_tests(glue: Glue, config: Config<'_>, args: &Args) -> Result<i32, String> {
+    // ignored tests are listed as well, whether they are run is decided by the harness arguments
+    let cukes = runtime::list_cukes(&config, Some(IGNORE_TAG))
+        .map_err(|err| format!("cucumber tests could not be run: {}", err))?;
+    let cuke_count = cukes.len();
+
+    let tests = cukes.into_iter()
+        .map(|cuke| Test {
+            name: test_name(&cuke.uri, &cuke.name, cuke.line),
+            selector: format!("{}:{}", cuke.uri, cuke.line),
+            ignored: cuke.tags.iter().any(|tag| tag == IGNORE_TAG),
+        })
+        .filter(|test| args.is_selected(&test.name))
+        .collect::<Vec<Test>>();
+    let output = Output::new(args.format);
+
+    if args.list {
+        output.list(&tests.iter().map(|test| test.name.as_str()).collect::<Vec<&str>>());
+        return Ok(0);
+    }
+
+    let mut summary = Summary {
+        filtered_out: cuke_count - tests.len(),
+        ..Summary::default()
+    };
+    let (tests_to_run, ignored_tests) = tests.iter()
+        .partition::<Vec<&Test>, _>(|test| match args.run_ignored {
+            RunIgnored::No => !test.ignored,
+            RunIgnored::Only => test.ignored,
+            RunIgnored::Yes => true,
+        });
+
+    output.run_started(tests.len());
+    for ignored_test in &ignored_tests {
+        output.test_ignored(&ignored_test.name);
+    }
+    summary.ignored = ignored_tests.len();
+
+    let start_instant = Instant::now();
+    if !tests_to_run.is_empty() {
+        let selectors = tests_to_run.iter()
+            .map(|test| test.selector.as_str())
+            .collect::<Vec<&str>>();
+
+        // the cukes were already filtered, so only the selected ones are run
+        let mut config: Config<'_> = config;
+        config.tags = &[];
+        config.names = &[];
+        config.lines = selectors.as_slice();
+        config.rerun_file = None;
+        config.threads = args.test_threads.or(config.threads);
+
+        let harness_listener = HarnessListener::new(output, config.strict, config.fail_on_flaky);
+        let run_outcome = runtime::run_with_listener(glue, config, Some(&harness_listener))
+            .map_err(|err| format!("cucumber tests could not be run: {}", err))?;
+
+        let (passed, failed) = harness_listener.into_results();
+        summary.passed = passed;
+        summary.failed = failed;
+        summary.run_failed = !run_outcome.success;
+    }
+    summary.duration = start_instant.elapsed();
+
+    output.run_finished(&summary);
+
+    Ok(if summary.is_ok() { 0 } else { 101 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_tests_after_uri_scenario_and_line() {
+        assert_eq!(test_name("calc/add.feature", "Add two numbers", 12),
+            "calc/add.feature::Add two numbers (line 12)");
+    }
+}
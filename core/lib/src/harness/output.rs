@@ -0,0 +1,241 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::api::TestResultStatus;
+use crate::api::event::{Event, EventListener};
+use crate::harness::args::Format;
+use crate::harness::test_name;
+
+/// Prints the progress and results of the tests in one of the libtest output formats.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Output {
+    format: Format,
+}
+
+/// The results of a test run for the final summary.
+#[derive(Debug, Default)]
+pub(crate) struct Summary {
+    pub passed: usize,
+    /// The names and error messages of the failed tests.
+    pub failed: Vec<(String, String)>,
+    pub ignored: usize,
+    pub filtered_out: usize,
+    pub duration: Duration,
+    /// Whether the run failed apart from its tests, e.g. because a global hook failed.
+    pub run_failed: bool,
+}
+
+impl Summary {
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty() && !self.run_failed
+    }
+}
+
+impl Output {
+    pub fn new(format: Format) -> Output {
+        Output {
+            format,
+        }
+    }
+
+    pub fn list(self, test_names: &[&str]) {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+
+        for test_name in test_names {
+            writeln!(stdout, "{}: test", test_name).unwrap();
+        }
+
+        if self.format == Format::Pretty {
+            writeln!(stdout).unwrap();
+            writeln!(stdout, "{} tests, 0 benchmarks", test_names.len()).unwrap();
+        }
+    }
+
+    pub fn run_started(self, test_count: usize) {
+        match self.format {
+            Format::Pretty | Format::Terse => {
+                println!();
+                println!("running {} tests", test_count);
+            }
+            Format::Json => {
+                println!(r#"{{ "type": "suite", "event": "started", "test_count": {} }}"#, test_count);
+            }
+        }
+    }
+
+    pub fn test_started(self, test_name: &str) {
+        if self.format == Format::Json {
+            println!(r#"{{ "type": "test", "event": "started", "name": "{}" }}"#, escape_json(test_name));
+        }
+    }
+
+    pub fn test_passed(self, test_name: &str) {
+        match self.format {
+            Format::Pretty => println!("test {} ... ok", test_name),
+            Format::Terse => print_terse("."),
+            Format::Json => {
+                println!(r#"{{ "type": "test", "name": "{}", "event": "ok" }}"#, escape_json(test_name));
+            }
+        }
+    }
+
+    pub fn test_failed(self, test_name: &str, message: &str) {
+        match self.format {
+            Format::Pretty => println!("test {} ... FAILED", test_name),
+            Format::Terse => print_terse("F"),
+            Format::Json => {
+                println!(r#"{{ "type": "test", "name": "{}", "event": "failed", "stdout": "{}" }}"#,
+                    escape_json(test_name), escape_json(message));
+            }
+        }
+    }
+
+    pub fn test_ignored(self, test_name: &str) {
+        self.test_started(test_name);
+
+        match self.format {
+            Format::Pretty => println!("test {} ... ignored", test_name),
+            Format::Terse => print_terse("i"),
+            Format::Json => {
+                println!(r#"{{ "type": "test", "name": "{}", "event": "ignored" }}"#, escape_json(test_name));
+            }
+        }
+    }
+
+    pub fn run_finished(self, summary: &Summary) {
+        if self.format == Format::Json {
+            println!(concat!(r#"{{ "type": "suite", "event": "{}", "passed": {}, "failed": {}, "#,
+                r#""ignored": {}, "measured": 0, "filtered_out": {}, "exec_time": {} }}"#),
+                if summary.is_ok() { "ok" } else { "failed" },
+                summary.passed, summary.failed.len(), summary.ignored, summary.filtered_out,
+                summary.duration.as_secs_f64());
+            return;
+        }
+
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+
+        if self.format == Format::Terse {
+            writeln!(stdout).unwrap();
+        }
+
+        if !summary.failed.is_empty() {
+            writeln!(stdout).unwrap();
+            writeln!(stdout, "failures:").unwrap();
+            writeln!(stdout).unwrap();
+            for (test_name, message) in &summary.failed {
+                writeln!(stdout, "---- {} stdout ----", test_name).unwrap();
+                writeln!(stdout, "{}", message).unwrap();
+                writeln!(stdout).unwrap();
+            }
+
+            writeln!(stdout).unwrap();
+            writeln!(stdout, "failures:").unwrap();
+            for (test_name, _) in &summary.failed {
+                writeln!(stdout, "    {}", test_name).unwrap();
+            }
+        }
+
+        writeln!(stdout).unwrap();
+        writeln!(stdout, "test result: {}. {} passed; {} failed; {} ignored; 0 measured; {} filtered out; \
+            finished in {:.2}s",
+            if summary.is_ok() { "ok" } else { "FAILED" },
+            summary.passed, summary.failed.len(), summary.ignored, summary.filtered_out,
+            summary.duration.as_secs_f64()).unwrap();
+        writeln!(stdout).unwrap();
+    }
+}
+
+fn print_terse(result: &str) {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    stdout.write_all(result.as_bytes()).unwrap();
+    stdout.flush().unwrap();
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Reports every finished test case as a test of the harness.
+#[derive(Debug)]
+pub(crate) struct HarnessListener {
+    output: Output,
+    strict: bool,
+    fail_on_flaky: bool,
+    /// The number of passed tests and the names and error messages of the failed tests.
+    results: Mutex<RefCell<(usize, Vec<(String, String)>)>>,
+}
+
+impl HarnessListener {
+    pub fn new(output: Output, strict: bool, fail_on_flaky: bool) -> HarnessListener {
+        HarnessListener {
+            output,
+            strict,
+            fail_on_flaky,
+            results: Mutex::new(RefCell::new((0, Vec::new()))),
+        }
+    }
+
+    pub fn into_results(self) -> (usize, Vec<(String, String)>) {
+        self.results.into_inner().unwrap().into_inner()
+    }
+}
+
+impl EventListener for HarnessListener {
+    fn on_event(&self, event: &Event<'_, '_>) {
+        match *event {
+            Event::TestCaseStarted { test_case, attempt: 0, .. } => {
+                let test_name = test_name(test_case.get_uri(), test_case.get_name(), test_case.get_example_line());
+                self.output.test_started(&test_name);
+            }
+            Event::TestCaseFinished { test_case, ref result, will_be_retried: false, .. } => {
+                let test_name = test_name(test_case.get_uri(), test_case.get_name(), test_case.get_example_line());
+                let status = result.status;
+
+                if status.is_ok(self.strict) && !(self.fail_on_flaky && status == TestResultStatus::Flaky) {
+                    self.output.test_passed(&test_name);
+                    self.results.lock().unwrap().borrow_mut().0 += 1;
+                } else {
+                    let message = result.get_error_message()
+                        .unwrap_or_else(|| format!("scenario is {}", status));
+                    self.output.test_failed(&test_name, &message);
+                    self.results.lock().unwrap().borrow_mut().1.push((test_name, message));
+                }
+            }
+            Event::TestSourceParseError { uri, error, .. } => {
+                let message = error.to_string();
+                self.output.test_failed(uri, &message);
+                self.results.lock().unwrap().borrow_mut().1.push((uri.to_owned(), message));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!(escape_json("a \"b\"\\c\nd\u{1}"), "a \\\"b\\\"\\\\c\\nd\\u0001");
+    }
+}
@@ -8,21 +8,61 @@ pub use gherkin;
 #[doc(hidden)] pub use cuke_runner_codegen::*;
 pub use cuke_runner_glue as glue;
 
-pub use crate::config::{Config, ExecutionMode};
-pub use crate::error::{Error, Result};
+pub use crate::api::{GlueCheck, RunOutcome};
+pub use crate::config::{Config, ConfigBuilder, ExecutionMode, ExecutionModeKind, OwnedConfig, Profile};
+pub use crate::error::{Error, Result, StepDefinitionCandidate};
 pub use crate::runtime::Glue;
 pub use crate::runtime::event_listener;
 
+use crate::runtime::event_listener::SyncTestSummaryListener;
+
 mod config;
 mod error;
 pub mod api;
+pub mod harness;
 mod runner;
 pub(crate) mod runtime;
 
+/// Runs the cucumber tests with exactly the given config and returns their outcome.
+///
+/// Unlike `execute_cucumber_tests`, the config is not overridden and failed tests do not panic,
+/// so that custom binaries can decide themselves what to print and how to exit.
+pub fn run(glue: Glue, config: Config<'_>) -> Result<RunOutcome> {
+    runtime::run(glue, config)
+}
+
+/// Matches the steps of the feature files that would be run with the config against all step definitions
+/// and reports the step definitions that match the same steps.
+///
+/// This is an opt-in check that finds overlapping step definitions before the test run starts,
+/// instead of when the first ambiguous step is run.
+pub fn check_glue(glue: &Glue, config: &Config<'_>) -> Result<GlueCheck> {
+    runtime::check_glue(glue, config)
+}
+
+/// Runs the cucumber tests with the given config,
+/// after overriding its values from the selected `cukes.toml` profile,
+/// `CUKE_*` environment variables and command line arguments.
 pub fn execute_cucumber_tests(glue: Glue, config: Config<'_>) {
-    let exit_status = runtime::run(glue, config);
+    let overrides = match config::Overrides::load() {
+        Ok(overrides) => overrides,
+        Err(err) => panic!("Cucumber tests could not be configured: {}", err),
+    };
+
+    let test_summary_listener = SyncTestSummaryListener::new();
+    let run_result = overrides.apply(config, |config| {
+        runtime::run_with_listener(glue, config, Some(&test_summary_listener))
+    });
+
+    let run_outcome = match run_result {
+        Ok(Ok(run_outcome)) => run_outcome,
+        Ok(Err(err)) => panic!("Cucumber tests could not be run: {}", err),
+        Err(err) => panic!("Cucumber tests could not be configured: {}", err),
+    };
+
+    test_summary_listener.print_test_summary();
 
-    if exit_status != 0 {
+    if !run_outcome.success {
         panic!("Cucumber test(s) failed");
     }
 }
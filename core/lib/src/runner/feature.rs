@@ -0,0 +1,133 @@
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use gherkin::ast;
+
+use crate::glue::feature::Feature;
+use crate::glue::global::Global;
+use crate::runner::{EventPublisher, FeatureHookTestStep};
+
+/// Runs the feature hooks of a feature file around the test cases of the feature.
+///
+/// The `BeforeFeature` hooks are run when the first test case of the feature is started
+/// and the `AfterFeature` hooks when the last test case of the feature is finished.
+/// Test cases that are started while the `BeforeFeature` hooks are still running wait for them,
+/// so the hooks run exactly once per feature in every execution mode.
+#[derive(Debug)]
+pub struct FeatureRun<'r, 'd> {
+    pub uri: &'d str,
+    pub feature: &'d ast::Feature,
+    pub before_hooks: Vec<FeatureHookTestStep<'r>>,
+    pub after_hooks: Vec<FeatureHookTestStep<'r>>,
+    dry_run: bool,
+    state: Mutex<FeatureRunState>,
+}
+
+#[derive(Debug)]
+enum FeatureRunState {
+    Pending {
+        global: Arc<Global>,
+        num_test_cases: usize,
+    },
+    Running {
+        feature_data: Arc<Feature>,
+        passed: bool,
+        remaining_test_cases: usize,
+    },
+    Finished,
+}
+
+impl<'r, 'd> FeatureRun<'r, 'd> {
+    pub fn new(
+        uri: &'d str,
+        feature: &'d ast::Feature,
+        before_hooks: Vec<FeatureHookTestStep<'r>>,
+        after_hooks: Vec<FeatureHookTestStep<'r>>,
+        global: Arc<Global>,
+        num_test_cases: usize,
+        dry_run: bool,
+    ) -> FeatureRun<'r, 'd>
+    {
+        FeatureRun {
+            uri,
+            feature,
+            before_hooks,
+            after_hooks,
+            dry_run,
+            state: Mutex::new(FeatureRunState::Pending {
+                global,
+                num_test_cases,
+            }),
+        }
+    }
+
+    /// Runs the `BeforeFeature` hooks if this is the first started test case of the feature.
+    ///
+    /// Returns the feature data for the test case and whether the test case
+    /// has to be skipped because a `BeforeFeature` hook did not pass.
+    pub fn start_test_case<EP: EventPublisher>(&self, event_publisher: &EP) -> (Arc<Feature>, bool) {
+        let mut state = self.state.lock().unwrap();
+
+        if let FeatureRunState::Pending { ref global, num_test_cases } = *state {
+            let mut feature_data = Feature::new(Arc::clone(global));
+            let passed = run_feature_hooks(self, &self.before_hooks, Some(&mut feature_data), true, event_publisher);
+
+            *state = FeatureRunState::Running {
+                feature_data: Arc::new(feature_data),
+                passed,
+                remaining_test_cases: num_test_cases,
+            };
+        }
+
+        match *state {
+            FeatureRunState::Running { ref feature_data, passed, .. } => (Arc::clone(feature_data), !passed),
+            _ => panic!("test case of feature \"{}\" started after the feature was finished", self.uri),
+        }
+    }
+
+    /// Runs the `AfterFeature` hooks if this is the last finished test case of the feature.
+    ///
+    /// The hooks fail without being run if the feature data handed out by `start_test_case`
+    /// is still used by a step that exceeded its timeout.
+    pub fn finish_test_case<EP: EventPublisher>(&self, event_publisher: &EP) {
+        let mut state = self.state.lock().unwrap();
+
+        let finished = match *state {
+            FeatureRunState::Running { ref mut remaining_test_cases, .. } => {
+                *remaining_test_cases -= 1;
+                *remaining_test_cases == 0
+            }
+            _ => panic!("test case of feature \"{}\" finished before it was started", self.uri),
+        };
+
+        if finished {
+            if let FeatureRunState::Running { feature_data, .. } = mem::replace(&mut *state, FeatureRunState::Finished) {
+                let mut feature_data = Arc::try_unwrap(feature_data).ok();
+                run_feature_hooks(self, &self.after_hooks, feature_data.as_mut(), false, event_publisher);
+            }
+        }
+    }
+}
+
+fn run_feature_hooks<EP: EventPublisher>(
+    feature_run: &FeatureRun<'_, '_>,
+    hooks: &[FeatureHookTestStep<'_>],
+    mut feature_data: Option<&mut Feature>,
+    skip_after_failure: bool,
+    event_publisher: &EP,
+) -> bool
+{
+    let mut skip = feature_run.dry_run;
+    let mut passed = true;
+
+    for hook in hooks {
+        let hook_result = hook.run(event_publisher, feature_run.uri, feature_run.feature,
+            feature_data.as_deref_mut(), skip);
+        if !hook_result.status.is_ok(true) {
+            passed = false;
+            skip = skip || skip_after_failure;
+        }
+    }
+
+    passed
+}
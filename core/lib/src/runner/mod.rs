@@ -1,35 +1,122 @@
 pub use self::event::EventPublisher;
 pub(crate) use self::event::{EventBus, SyncEventBus};
-pub use self::test_step::{HookTestStep, CukeStepTestStep};
+pub use self::feature::FeatureRun;
+pub use self::test_step::{FeatureHookTestStep, GlobalHookTestStep, HookTestStep, CukeStepTestStep};
 
 mod event;
+mod feature;
 mod test_step;
 
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
 use gherkin::cuke::{Cuke, Tag};
 
-use crate::api::HookType;
-use crate::runtime::{Glue, HookDefinition};
+use crate::api::{self, HookType};
+use crate::api::event::Event;
+use crate::glue::feature::Feature;
+use crate::glue::global::Global;
+use crate::glue::timeout;
+use crate::runtime::{FeatureHookDefinition, Glue, GlobalHookDefinition, HookDefinition};
 use crate::runtime::{self, TestCase, StepDefinitionMatch, HookDefinitionMatch};
 
 pub struct Runner {
     glue: Glue,
+    global: Arc<Global>,
     dry_run: bool,
+    step_timeout: Option<Duration>,
+    retries: u32,
 }
 
 impl Runner {
-    pub fn new(glue: Glue, dry_run: bool) -> Runner {
+    pub fn new(glue: Glue, dry_run: bool, step_timeout: Option<Duration>, retries: u32) -> Runner {
         Runner {
             glue,
+            global: Arc::new(Global::new()),
             dry_run,
+            step_timeout,
+            retries,
         }
     }
 
-    pub fn run<EP: EventPublisher>(&self, uri: &str, cuke: Cuke<'_>, event_publisher: &EP) {
-        let test_case = self.create_test_case(uri, &cuke);
+    /// Sends all step definitions of the glue, ordered by their location.
+    pub fn send_step_definitions<EP: EventPublisher>(&self, event_publisher: &EP) {
+        let step_definitions = self.glue.get_step_definitions().iter()
+            .map(|step_definition| &**step_definition as &dyn api::StepDefinition)
+            .collect::<Vec<&dyn api::StepDefinition>>();
+
+        event_publisher.send(Event::StepDefinitionsRead {
+            time: SystemTime::now(),
+            step_definitions: &step_definitions,
+        });
+    }
+
+    /// Runs the `BeforeAll` hooks and returns whether all of them passed.
+    ///
+    /// The remaining hooks are skipped after the first hook that did not pass.
+    pub fn run_before_all_hooks<EP: EventPublisher>(&mut self, event_publisher: &EP) -> bool {
+        let global = Arc::get_mut(&mut self.global)
+            .expect("global data must not be shared before the test run started");
+        let hook_definitions = self.glue.get_before_all_hooks();
+
+        run_global_hooks(hook_definitions, HookType::BeforeAll, Some(global), self.dry_run, true, event_publisher)
+    }
+
+    /// Runs the `AfterAll` hooks.
+    ///
+    /// All hooks are run even if a previous hook did not pass, so that every hook
+    /// gets the chance to clean up its resources.
+    /// The hooks fail without being run if the global data is still used by a step
+    /// that exceeded its timeout.
+    pub fn run_after_all_hooks<EP: EventPublisher>(&mut self, event_publisher: &EP) {
+        let global = Arc::get_mut(&mut self.global);
+        let hook_definitions = self.glue.get_after_all_hooks();
+
+        run_global_hooks(hook_definitions, HookType::AfterAll, global, self.dry_run, false, event_publisher);
+    }
+
+    /// Creates the run of a feature file with the feature hooks
+    /// that match the tags of at least one of the given cukes.
+    ///
+    /// The cukes must all belong to the feature file and must not be empty.
+    pub fn create_feature_run<'d>(&self, uri: &'d str, cukes: &[Cuke<'d>]) -> FeatureRun<'_, 'd> {
+        let feature = cukes.first()
+            .map(|cuke| cuke.feature)
+            .expect("a feature run needs at least one cuke");
+
+        FeatureRun::new(
+            uri,
+            feature,
+            self.create_feature_hooks(cukes, self.glue.get_before_feature_hooks(), HookType::BeforeFeature),
+            self.create_feature_hooks(cukes, self.glue.get_after_feature_hooks(), HookType::AfterFeature),
+            Arc::clone(&self.global),
+            cukes.len(),
+            self.dry_run,
+        )
+    }
+
+    fn create_feature_hooks<'h>(&self, cukes: &[Cuke<'_>], hook_definitions: &'h [FeatureHookDefinition],
+        hook_type: HookType) -> Vec<FeatureHookTestStep<'h>>
+    {
+        hook_definitions.iter()
+            .filter(|hook_definition| cukes.iter().any(|cuke| hook_definition.matches(&cuke.tags)))
+            .map(|hook_definition| FeatureHookTestStep {
+                hook_definition,
+                hook_type,
+            })
+            .collect()
+    }
+
+    pub fn run<EP: EventPublisher>(&self, feature_run: &FeatureRun<'_, '_>, cuke: Cuke<'_>, event_publisher: &EP) {
+        let (feature_data, skip) = feature_run.start_test_case(event_publisher);
+        let test_case = self.create_test_case(feature_run.uri, &cuke, feature_data, skip);
         runtime::test_case::run(test_case, event_publisher);
+        feature_run.finish_test_case(event_publisher);
     }
 
-    fn create_test_case<'c, 's: 'c>(&'s self, uri: &'c str, cuke: &'c Cuke<'_>) -> TestCase<'c> {
+    fn create_test_case<'c, 's: 'c>(&'s self, uri: &'c str, cuke: &'c Cuke<'_>,
+        feature_data: Arc<Feature>, skip: bool) -> TestCase<'c>
+    {
         let (
             before_hooks,
             after_hooks,
@@ -49,13 +136,24 @@ impl Runner {
             )
         };
 
+        let (scenario_timeout, retries, invalid_tag) =
+            match (scenario_timeout(&cuke.tags), scenario_retries(&cuke.tags)) {
+                (Ok(scenario_timeout), Ok(retries)) => (scenario_timeout, retries.unwrap_or(self.retries), None),
+                (Err(invalid_tag), _) | (_, Err(invalid_tag)) => (None, 0, Some(invalid_tag)),
+            };
+
         TestCase {
             uri,
             cuke,
             test_steps,
             before_hooks,
             after_hooks,
-            dry_run: self.dry_run,
+            feature_data,
+            skip: self.dry_run || skip,
+            invalid_tag,
+            step_timeout: self.step_timeout,
+            scenario_timeout,
+            retries,
         }
     }
 
@@ -151,3 +249,51 @@ impl Runner {
         self.create_hooks(tags, hook_definitions, HookType::AfterStep)
     }
 }
+
+/// Returns the timeout of the last `@timeout(...)` tag of a scenario.
+fn scenario_timeout(tags: &[Tag<'_>]) -> Result<Option<Duration>, String> {
+    tags.iter().rev()
+        .find_map(|tag| timeout::parse_timeout_tag(&tag.name)
+            .map(|result| result.map_err(|err| format!("\"{}\": {}", tag.name, err))))
+        .transpose()
+}
+
+/// Returns the number of retries of the last `@retry(...)` tag of a scenario.
+fn scenario_retries(tags: &[Tag<'_>]) -> Result<Option<u32>, String> {
+    tags.iter().rev()
+        .find(|tag| tag.name.starts_with("@retry(") && tag.name.ends_with(')'))
+        .map(|tag| {
+            let retries = &tag.name["@retry(".len()..tag.name.len() - 1];
+            retries.trim().parse::<u32>()
+                .map_err(|err| format!("\"{}\": {}", tag.name, err))
+        })
+        .transpose()
+}
+
+fn run_global_hooks<EP: EventPublisher>(
+    hook_definitions: &[GlobalHookDefinition],
+    hook_type: HookType,
+    mut global: Option<&mut Global>,
+    dry_run: bool,
+    skip_after_failure: bool,
+    event_publisher: &EP,
+) -> bool
+{
+    let mut skip = dry_run;
+    let mut passed = true;
+
+    for hook_definition in hook_definitions {
+        let hook = GlobalHookTestStep {
+            hook_definition,
+            hook_type,
+        };
+
+        let hook_result = hook.run(event_publisher, global.as_deref_mut(), skip);
+        if !hook_result.status.is_ok(true) {
+            passed = false;
+            skip = skip || skip_after_failure;
+        }
+    }
+
+    passed
+}
@@ -1,12 +1,27 @@
-use std::time::{SystemTime, Duration};
+use std::iter;
+use std::mem;
+use std::panic;
+use std::ptr;
+use std::sync::Arc;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
-use gherkin::cuke;
+use gherkin::{ast, cuke};
 
 use crate::error::{Result, Error};
 use crate::api::{self, event::Event, HookType, GlueCodeLocation, TestResult, TestResultStatus};
+use crate::glue;
+use crate::glue::step::StepKeyword;
 use crate::glue::step::argument::StepArgument;
 use crate::runner::EventPublisher;
-use crate::runtime::{TestCase, StepDefinitionMatch, Scenario};
+use crate::glue::feature::Feature;
+use crate::glue::global::Global;
+use crate::runtime::{
+    FeatureHookDefinition, GlobalHookDefinition, TestCase, StepDefinition, StepDefinitionMatch,
+    StepJob, Scenario,
+};
+use crate::runtime::snippet::{self, SnippetArgument};
 
 #[derive(Debug)]
 pub struct HookTestStep<'s> {
@@ -38,6 +53,60 @@ impl<'s> api::HookTestStep<'s> for HookTestStep<'s> {
     }
 }
 
+#[derive(Debug)]
+pub struct GlobalHookTestStep<'s> {
+    pub hook_definition: &'s GlobalHookDefinition,
+    pub hook_type: HookType,
+}
+
+impl<'s> GlobalHookTestStep<'s> {
+    pub fn run<EP: EventPublisher>(&self, event_publisher: &EP, global: Option<&mut Global>, skip: bool)
+        -> TestResult
+    {
+        run_global_hook(self, event_publisher, global, skip)
+    }
+}
+
+impl<'s> api::HookTestStep<'s> for GlobalHookTestStep<'s> {
+    fn get_glue_code_location(&self) -> Option<&GlueCodeLocation> {
+        Some(self.hook_definition.get_location())
+    }
+
+    fn get_hook_type(&self) -> HookType {
+        self.hook_type
+    }
+}
+
+#[derive(Debug)]
+pub struct FeatureHookTestStep<'s> {
+    pub hook_definition: &'s FeatureHookDefinition,
+    pub hook_type: HookType,
+}
+
+impl<'s> FeatureHookTestStep<'s> {
+    pub fn run<EP: EventPublisher>(
+        &self,
+        event_publisher: &EP,
+        uri: &str,
+        feature: &ast::Feature,
+        feature_data: Option<&mut Feature>,
+        skip: bool,
+    ) -> TestResult
+    {
+        run_feature_hook(self, event_publisher, uri, feature, feature_data, skip)
+    }
+}
+
+impl<'s> api::HookTestStep<'s> for FeatureHookTestStep<'s> {
+    fn get_glue_code_location(&self) -> Option<&GlueCodeLocation> {
+        Some(self.hook_definition.get_location())
+    }
+
+    fn get_hook_type(&self) -> HookType {
+        self.hook_type
+    }
+}
+
 #[derive(Debug)]
 pub struct CukeStepTestStep<'s> {
     pub uri: String,
@@ -72,6 +141,9 @@ impl<'s> CukeStepTestStep<'s> {
         let test_step = &api::TestStep::Cuke(self as &dyn api::CukeStepTestStep<'_>);
         let self_result = run_test_step(test_case, test_step, &self.step_definition_match,
                 event_publisher, scenario, skip_self);
+        if self_result.status == TestResultStatus::Undefined {
+            self.send_snippet(event_publisher, test_case);
+        }
         results.push(self_result);
 
         for after_step_hook_step in &self.after_step_hook_steps {
@@ -83,6 +155,33 @@ impl<'s> CukeStepTestStep<'s> {
             .max_by_key(TestResult::get_status)
             .expect("at least one test result")
     }
+
+    fn send_snippet<EP: EventPublisher>(&self, event_publisher: &EP, test_case: &TestCase<'_>) {
+        let step = self.step_definition_match.get_step();
+
+        // "And" and "But" steps use the keyword of the steps before them
+        let position = test_case.test_steps.iter()
+            .position(|test_step| ptr::eq(test_step, self))
+            .unwrap_or(0);
+        let keywords = test_case.test_steps[..position].iter()
+            .map(|test_step| &*test_step.step_definition_match.get_step().keyword)
+            .chain(iter::once(&*step.keyword));
+
+        let argument = match &step.argument {
+            Some(cuke::Argument::String(_)) => Some(SnippetArgument::DocString),
+            Some(cuke::Argument::Table(_)) => Some(SnippetArgument::DataTable),
+            None => None,
+        };
+
+        let snippet = snippet::generate_snippet(snippet::step_attribute(keywords), &step.text, argument);
+        event_publisher.send(Event::SnippetSuggested {
+            time: SystemTime::now(),
+            uri: test_case.uri,
+            test_case,
+            test_step: self,
+            snippet: &snippet,
+        });
+    }
 }
 
 impl<'s> api::CukeStepTestStep<'s> for CukeStepTestStep<'s> {
@@ -91,7 +190,23 @@ impl<'s> api::CukeStepTestStep<'s> for CukeStepTestStep<'s> {
     }
 
     fn get_pattern(&self) -> Option<&str> {
-        self.step_definition_match.get_pattern().map(String::as_str)
+        self.step_definition_match.get_pattern()
+    }
+
+    fn get_step_definition_name(&self) -> Option<&str> {
+        self.step_definition_match.get_step_definition()
+            .map(StepDefinition::get_name)
+    }
+
+    fn get_step_definition_keyword(&self) -> Option<StepKeyword> {
+        self.step_definition_match.get_step_definition()
+            .map(StepDefinition::get_keyword)
+    }
+
+    fn get_parameter_types(&self) -> &[&str] {
+        self.step_definition_match.get_step_definition()
+            .map(StepDefinition::get_parameter_types)
+            .unwrap_or(&[])
     }
 
     fn get_cuke_step(&self) -> &cuke::Step<'_> {
@@ -149,7 +264,14 @@ fn run_test_step<EP: EventPublisher>(
         test_step,
     });
 
-    let step_result = execute_step(definition_match, scenario, skip);
+    let timeout = test_case.get_step_timeout(definition_match.get_timeout(), scenario.get_elapsed());
+    let step_result = match timeout {
+        Some(timeout) if !skip => {
+            execute_step_with_timeout(test_case, test_step, definition_match, scenario, timeout)
+        }
+        _ => execute_step(definition_match, scenario, skip),
+    };
+
     let (status, error) = match step_result {
         Ok(test_result_type) => (test_result_type, None),
         Err(error) => (map_error_to_status(&error), Some(error)),
@@ -175,6 +297,90 @@ fn run_test_step<EP: EventPublisher>(
     result
 }
 
+// #[inline(never)] to keep the function in the stack trace for the panic handler
+// to identify that this is an panic in a global hook
+#[inline(never)]
+fn run_global_hook<EP: EventPublisher>(
+    hook: &GlobalHookTestStep<'_>,
+    event_publisher: &EP,
+    global: Option<&mut Global>,
+    skip: bool,
+) -> TestResult
+{
+    let start_time = SystemTime::now();
+    event_publisher.send(Event::GlobalHookStarted {
+        time: start_time,
+        hook: hook as &dyn api::HookTestStep<'_>,
+    });
+
+    let (status, error) = match global {
+        _ if skip => (TestResultStatus::Skipped, None),
+        Some(global) => match hook.hook_definition.execute(global) {
+            Ok(()) => (TestResultStatus::Passed, None),
+            Err(error) => (map_error_to_status(&error), Some(error)),
+        },
+        None => (TestResultStatus::Failed, Some(Error::HookDataInUse)),
+    };
+
+    let stop_time = SystemTime::now();
+    let duration = match stop_time.duration_since(start_time) {
+        Ok(duration) => duration,
+        Err(system_time_error) => system_time_error.duration(),
+    };
+    let result = map_status_to_result(status, error, duration);
+    event_publisher.send(Event::GlobalHookFinished {
+        time: stop_time,
+        hook: hook as &dyn api::HookTestStep<'_>,
+        result: &result,
+    });
+    result
+}
+
+// #[inline(never)] to keep the function in the stack trace for the panic handler
+// to identify that this is an panic in a feature hook
+#[inline(never)]
+fn run_feature_hook<EP: EventPublisher>(
+    hook: &FeatureHookTestStep<'_>,
+    event_publisher: &EP,
+    uri: &str,
+    feature: &ast::Feature,
+    feature_data: Option<&mut Feature>,
+    skip: bool,
+) -> TestResult
+{
+    let start_time = SystemTime::now();
+    event_publisher.send(Event::FeatureHookStarted {
+        time: start_time,
+        uri,
+        feature,
+        hook: hook as &dyn api::HookTestStep<'_>,
+    });
+
+    let (status, error) = match feature_data {
+        _ if skip => (TestResultStatus::Skipped, None),
+        Some(feature_data) => match hook.hook_definition.execute(feature_data) {
+            Ok(()) => (TestResultStatus::Passed, None),
+            Err(error) => (map_error_to_status(&error), Some(error)),
+        },
+        None => (TestResultStatus::Failed, Some(Error::HookDataInUse)),
+    };
+
+    let stop_time = SystemTime::now();
+    let duration = match stop_time.duration_since(start_time) {
+        Ok(duration) => duration,
+        Err(system_time_error) => system_time_error.duration(),
+    };
+    let result = map_status_to_result(status, error, duration);
+    event_publisher.send(Event::FeatureHookFinished {
+        time: stop_time,
+        uri,
+        feature,
+        hook: hook as &dyn api::HookTestStep<'_>,
+        result: &result,
+    });
+    result
+}
+
 fn execute_step(
     definition_match: &StepDefinitionMatch<'_>,
     scenario: &mut Scenario<'_, '_>,
@@ -192,9 +398,106 @@ fn execute_step(
     Ok(test_result_type)
 }
 
+/// Runs the step on its own thread, so that a step that does not finish
+/// within the timeout can be abandoned.
+///
+/// The `Send` scenario data is moved to the step thread and only returned if the step finished in time,
+/// otherwise the remaining steps and hooks of the scenario get empty scenario data.
+/// The step fails without being run if the scenario has data that is not `Send`.
+fn execute_step_with_timeout(
+    test_case: &TestCase<'_>,
+    test_step: &api::TestStep<'_, '_>,
+    definition_match: &StepDefinitionMatch<'_>,
+    scenario: &mut Scenario<'_, '_>,
+    timeout: Duration,
+) -> Result<TestResultStatus>
+{
+    let step_job = match definition_match.to_step_job() {
+        Some(step_job) => step_job,
+        None => return execute_step(definition_match, scenario, false),
+    };
+
+    if scenario.glue_scenario.has_non_send_data() {
+        return Err(non_send_data_error(test_step));
+    }
+
+    let start_instant = Instant::now();
+    let glue_scenario = mem::replace(&mut scenario.glue_scenario,
+        glue::scenario::Scenario::new(Arc::clone(&test_case.feature_data)));
+
+    match run_step_job(step_job, glue_scenario.into_send(), timeout)? {
+        Some((step_result, send_scenario, has_non_send_data)) => {
+            scenario.glue_scenario = glue::scenario::Scenario::from(send_scenario);
+            match step_result {
+                Ok(()) if has_non_send_data => Err(non_send_data_error(test_step)),
+                step_result => step_result.map(|()| TestResultStatus::Passed),
+            }
+        }
+        None => Err(Error::Timeout {
+            step: test_step_name(test_step),
+            elapsed: start_instant.elapsed(),
+            timeout,
+        }),
+    }
+}
+
+/// Runs the step job on a new thread and waits at most for the timeout for it to finish.
+///
+/// Returns the step result, the scenario data and whether the step set data that is not `Send`
+/// (which is dropped), or `None` if the step did not finish in time, its thread is then left running.
+fn run_step_job(step_job: StepJob, send_scenario: glue::scenario::SendScenario, timeout: Duration)
+    -> Result<Option<(Result<()>, glue::scenario::SendScenario, bool)>>
+{
+    let (sender, receiver) = mpsc::channel();
+    let thread_name = thread::current().name().unwrap_or("cuke-runner").to_owned();
+
+    let step_thread = thread::Builder::new()
+        .name(thread_name)
+        .spawn(move || {
+            let mut glue_scenario = glue::scenario::Scenario::from(send_scenario);
+            let step_result = execute_step_job(&step_job, &mut glue_scenario);
+            let has_non_send_data = glue_scenario.has_non_send_data();
+            // the receiver is already gone if the step did not finish in time
+            let _ = sender.send((step_result, glue_scenario.into_send(), has_non_send_data));
+        })?;
+
+    match receiver.recv_timeout(timeout) {
+        Ok(step_job_result) => Ok(Some(step_job_result)),
+        Err(RecvTimeoutError::Timeout) => Ok(None),
+        // step and hook functions catch the panics of the glue code,
+        // so this is a panic of the cuke runner that is passed on
+        Err(RecvTimeoutError::Disconnected) => match step_thread.join() {
+            Ok(()) => unreachable!("step thread finished without sending its result"),
+            Err(panic_payload) => panic::resume_unwind(panic_payload),
+        },
+    }
+}
+
+// #[inline(never)] to keep the function in the stack trace of the step thread
+// for the panic handler to identify that this is an panic in a cucumber test
+#[inline(never)]
+fn execute_step_job(step_job: &StepJob, glue_scenario: &mut glue::scenario::Scenario) -> Result<()> {
+    step_job.run(glue_scenario)
+}
+
+fn non_send_data_error(test_step: &api::TestStep<'_, '_>) -> Error {
+    Error::FromScenario(glue::scenario::FromScenarioError::new(format!(
+        "{} has a timeout and runs on its own thread, \
+        so its scenario data must be set with `Scenario::set_send` instead of `Scenario::set`",
+        test_step_name(test_step))))
+}
+
+fn test_step_name(test_step: &api::TestStep<'_, '_>) -> String {
+    match test_step {
+        api::TestStep::Hook(hook) => format!("{:?} hook", hook.get_hook_type()),
+        api::TestStep::Cuke(cuke_step) =>
+            format!("step \"{}{}\"", cuke_step.get_step_keyword(), cuke_step.get_step_text()),
+    }
+}
+
 fn map_error_to_status(error: &Error) -> TestResultStatus {
     match error {
-        Error::AmbiguousStepDefinitions => TestResultStatus::Ambiguous,
+        Error::AmbiguousStepDefinitions { .. } => TestResultStatus::Ambiguous,
         Error::UndefinedStepDefinition => TestResultStatus::Undefined,
         Error::Pending => TestResultStatus::Pending,
         _ => TestResultStatus::Failed,
@@ -224,3 +527,97 @@ fn map_status_to_result(status: TestResultStatus, error: Option<Error>, duration
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::rc::Rc;
+
+    use crate::glue::error::ExecutionError;
+    use crate::glue::hook::StaticHookDef;
+    use crate::glue::location::StaticGlueCodeLocation;
+    use crate::runtime::HookDefinition;
+
+    use super::*;
+
+    fn hook_fn(_scenario: &mut glue::scenario::Scenario) -> ::std::result::Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn sleeping_hook_fn(_scenario: &mut glue::scenario::Scenario) -> ::std::result::Result<(), ExecutionError> {
+        thread::sleep(Duration::from_secs(2));
+        Ok(())
+    }
+
+    fn non_send_data_hook_fn(scenario: &mut glue::scenario::Scenario)
+        -> ::std::result::Result<(), ExecutionError>
+    {
+        scenario.set(Rc::new(1));
+        scenario.set_send(2);
+        Ok(())
+    }
+
+    static HOOK: StaticHookDef = StaticHookDef {
+        name: "hook",
+        order: 0,
+        tag_expression: "",
+        timeout: Some(Duration::from_secs(10)),
+        hook_fn,
+        hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/hooks.rs", line: 1 },
+    };
+
+    static SLEEPING_HOOK: StaticHookDef = StaticHookDef {
+        name: "sleeping_hook",
+        order: 0,
+        tag_expression: "",
+        timeout: Some(Duration::from_millis(50)),
+        hook_fn: sleeping_hook_fn,
+        hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/hooks.rs", line: 2 },
+    };
+
+    static NON_SEND_DATA_HOOK: StaticHookDef = StaticHookDef {
+        name: "non_send_data_hook",
+        order: 0,
+        tag_expression: "",
+        timeout: Some(Duration::from_secs(10)),
+        hook_fn: non_send_data_hook_fn,
+        hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/hooks.rs", line: 3 },
+    };
+
+    fn step_job(static_hook_def: &StaticHookDef) -> StepJob {
+        StepJob::Hook(HookDefinition::from((Path::new("/crate/tests"), &static_hook_def)))
+    }
+
+    #[test]
+    fn returns_steps_that_finish_within_the_timeout() {
+        let step_job_result = run_step_job(step_job(&HOOK), glue::scenario::SendScenario::default(),
+            Duration::from_secs(10)).unwrap();
+
+        let (step_result, _send_scenario, has_non_send_data) = step_job_result.expect("step should finish in time");
+        assert!(step_result.is_ok());
+        assert!(!has_non_send_data);
+    }
+
+    #[test]
+    fn returns_only_the_send_data_of_steps() {
+        let step_job_result = run_step_job(step_job(&NON_SEND_DATA_HOOK), glue::scenario::SendScenario::default(),
+            Duration::from_secs(10)).unwrap();
+
+        let (step_result, send_scenario, has_non_send_data) = step_job_result.expect("step should finish in time");
+        let glue_scenario = glue::scenario::Scenario::from(send_scenario);
+        assert!(step_result.is_ok());
+        assert!(has_non_send_data);
+        assert_eq!(glue_scenario.get::<i32>(), Some(&2));
+        assert!(glue_scenario.get::<Rc<i32>>().is_none());
+    }
+
+    #[test]
+    fn abandons_steps_that_exceed_the_timeout() {
+        let start_instant = Instant::now();
+        let step_job_result = run_step_job(step_job(&SLEEPING_HOOK), glue::scenario::SendScenario::default(),
+            Duration::from_millis(50)).unwrap();
+
+        assert!(step_job_result.is_none());
+        assert!(start_instant.elapsed() < Duration::from_secs(2));
+    }
+}
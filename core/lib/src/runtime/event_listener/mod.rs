@@ -1,5 +1,7 @@
-pub(crate) use self::exit_status::*;
+pub(crate) use self::rerun_file::*;
+pub(crate) use self::run_outcome::*;
 pub(crate) use self::test_summary::*;
 
-mod exit_status;
+mod rerun_file;
+mod run_outcome;
 mod test_summary;
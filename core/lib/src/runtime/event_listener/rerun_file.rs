@@ -0,0 +1,79 @@
+use std::cell::RefCell;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::api::event::{Event, EventListener};
+
+/// The name of the rerun file in the output directory.
+pub const RERUN_FILE_NAME: &str = "rerun.txt";
+
+/// Collects the `uri:line` of every scenario that did not pass,
+/// so that exactly these scenarios can be run again with `Config::rerun_file`.
+///
+/// The line of a scenario outline is the line of its example row,
+/// so that only the failed examples are run again.
+#[derive(Debug, Default)]
+pub struct SyncRerunFileListener {
+    failed_scenarios: Mutex<RefCell<Vec<(String, u32)>>>,
+}
+
+impl SyncRerunFileListener {
+    pub fn new() -> SyncRerunFileListener {
+        SyncRerunFileListener::default()
+    }
+
+    pub fn write_rerun_file(&self, output_dir: &Path) -> io::Result<()> {
+        let failed_scenarios_lock = self.failed_scenarios.lock().unwrap();
+        let mut failed_scenarios = failed_scenarios_lock.borrow_mut();
+        write_rerun_file(&mut failed_scenarios, output_dir)
+    }
+}
+
+impl EventListener for SyncRerunFileListener {
+    fn on_event(&self, event: &Event<'_, '_>) {
+        if let Event::TestCaseFinished { test_case, ref result, will_be_retried: false, .. } = *event {
+            if !result.status.is_ok(true) {
+                self.failed_scenarios.lock().unwrap().borrow_mut()
+                    .push((test_case.get_uri().to_owned(), test_case.get_example_line()));
+            }
+        }
+    }
+}
+
+/// Writes one `uri:line` per line, sorted so that parallel runs produce the same file.
+///
+/// The file is also written if all scenarios passed,
+/// so that a stale rerun file of a previous run is never picked up.
+fn write_rerun_file(failed_scenarios: &mut Vec<(String, u32)>, output_dir: &Path) -> io::Result<()> {
+    failed_scenarios.sort();
+    failed_scenarios.dedup();
+
+    let content = format_rerun_file(failed_scenarios);
+
+    fs::create_dir_all(output_dir)?;
+    fs::write(output_dir.join(RERUN_FILE_NAME), content)
+}
+
+fn format_rerun_file(failed_scenarios: &[(String, u32)]) -> String {
+    failed_scenarios.iter()
+        .map(|(uri, line)| format!("{}:{}\n", uri, line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_one_scenario_per_line() {
+        let failed_scenarios = vec![
+            ("a.feature".to_owned(), 3),
+            ("b/c.feature".to_owned(), 12),
+        ];
+
+        assert_eq!(format_rerun_file(&failed_scenarios), "a.feature:3\nb/c.feature:12\n");
+        assert_eq!(format_rerun_file(&[]), "");
+    }
+}
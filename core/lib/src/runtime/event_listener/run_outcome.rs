@@ -0,0 +1,121 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::api::{FailedScenario, RunOutcome, TestResultStatus};
+use crate::api::event::{Event, EventListener};
+
+/// Counts the final status of every scenario and collects the scenarios and hooks that fail the run.
+#[derive(Debug)]
+pub struct SyncRunOutcomeListener {
+    run_results: Mutex<RefCell<RunResults>>,
+    strict: bool,
+    fail_on_flaky: bool,
+}
+
+#[derive(Debug, Default)]
+struct RunResults {
+    status_counts: BTreeMap<TestResultStatus, usize>,
+    failed_scenarios: Vec<FailedScenario>,
+    failed: bool,
+}
+
+impl SyncRunOutcomeListener {
+    pub fn new(strict: bool, fail_on_flaky: bool) -> SyncRunOutcomeListener {
+        SyncRunOutcomeListener {
+            run_results: Default::default(),
+            strict,
+            fail_on_flaky,
+        }
+    }
+
+    pub fn get_run_outcome(&self, duration: Duration) -> RunOutcome {
+        let run_results_lock = self.run_results.lock().unwrap();
+        let run_results = run_results_lock.borrow();
+
+        RunOutcome {
+            status_counts: run_results.status_counts.clone(),
+            failed_scenarios: run_results.failed_scenarios.clone(),
+            duration,
+            success: !run_results.failed,
+        }
+    }
+}
+
+impl EventListener for SyncRunOutcomeListener {
+    fn on_event(&self, event: &Event<'_, '_>) {
+        match *event {
+            Event::TestCaseFinished { test_case, ref result, will_be_retried: false, .. } => {
+                let run_results_lock = self.run_results.lock().unwrap();
+                let mut run_results = run_results_lock.borrow_mut();
+
+                *run_results.status_counts.entry(result.status).or_insert(0) += 1;
+
+                if fails_run(result.status, self.strict, self.fail_on_flaky) {
+                    run_results.failed_scenarios.push(FailedScenario {
+                        uri: test_case.get_uri().to_owned(),
+                        line: test_case.get_example_line(),
+                        name: test_case.get_name().to_owned(),
+                        status: result.status,
+                        error_message: result.get_error_message(),
+                    });
+                    run_results.failed = true;
+                }
+            }
+            Event::FeatureHookFinished { ref result, .. } |
+            Event::GlobalHookFinished { ref result, .. } => {
+                if fails_run(result.status, self.strict, self.fail_on_flaky) {
+                    self.run_results.lock().unwrap().borrow_mut().failed = true;
+                }
+            }
+            Event::TestSourceParseError { .. } => {
+                self.run_results.lock().unwrap().borrow_mut().failed = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn fails_run(status: TestResultStatus, strict: bool, fail_on_flaky: bool) -> bool {
+    !status.is_ok(strict) || (fail_on_flaky && status == TestResultStatus::Flaky)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flaky_fails_only_when_requested() {
+        assert!(!fails_run(TestResultStatus::Passed, true, true));
+        assert!(!fails_run(TestResultStatus::Flaky, true, false));
+        assert!(fails_run(TestResultStatus::Flaky, true, true));
+    }
+
+    #[test]
+    fn pending_fails_only_when_strict() {
+        assert!(!fails_run(TestResultStatus::Pending, false, false));
+        assert!(fails_run(TestResultStatus::Pending, true, false));
+        assert!(fails_run(TestResultStatus::Failed, false, false));
+    }
+
+    #[test]
+    fn parse_errors_fail_the_run() {
+        let listener = SyncRunOutcomeListener::new(false, false);
+        let error = crate::error::Error::InvalidTag("@retry(x)".to_owned());
+
+        listener.on_event(&Event::TestSourceParseError {
+            time: std::time::SystemTime::now(),
+            uri: "calc.feature",
+            line: None,
+            column: None,
+            error: &error,
+        });
+
+        let run_outcome = listener.get_run_outcome(Duration::from_secs(1));
+
+        assert!(!run_outcome.success);
+        assert_eq!(run_outcome.scenario_count(), 0);
+        assert!(run_outcome.failed_scenarios.is_empty());
+    }
+}
@@ -6,17 +6,21 @@ use std::time::Instant;
 use gherkin::ast::Scenario;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
-use crate::api::{TestCase, TestResultStatus};
+use crate::api::{HookTestStep, TestCase, TestResultStatus};
 use crate::api::event::{Event, EventListener};
 
 #[derive(Debug, Default)]
 struct TestSummary {
     passed: usize,
     skipped: Vec<TestInfo>,
+    flaky: Vec<TestInfo>,
     pending: Vec<TestInfo>,
     undefined: Vec<TestInfo>,
     ambiguous: Vec<TestInfo>,
     failed: Vec<TestInfo>,
+    failed_hooks: Vec<HookInfo>,
+    parse_errors: Vec<ParseErrorInfo>,
+    snippets: Vec<String>,
 }
 
 impl TestSummary {
@@ -26,12 +30,25 @@ impl TestSummary {
         match status {
             TestResultStatus::Passed => self.passed += 1,
             TestResultStatus::Skipped => self.skipped.push(TestInfo::from((scenario, test_case))),
+            TestResultStatus::Flaky => self.flaky.push(TestInfo::from((scenario, test_case))),
             TestResultStatus::Pending => self.pending.push(TestInfo::from((scenario, test_case))),
             TestResultStatus::Undefined => self.undefined.push(TestInfo::from((scenario, test_case))),
             TestResultStatus::Ambiguous => self.ambiguous.push(TestInfo::from((scenario, test_case))),
             TestResultStatus::Failed => self.failed.push(TestInfo::from((scenario, test_case))),
         }
     }
+
+    fn add_snippet(&mut self, snippet: &str) {
+        if !self.snippets.iter().any(|existing| existing == snippet) {
+            self.snippets.push(snippet.to_owned());
+        }
+    }
+
+    fn add_hook_result(&mut self, hook: &dyn HookTestStep<'_>, status: TestResultStatus) {
+        if !status.is_ok(true) {
+            self.failed_hooks.push(HookInfo::from(hook));
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -54,30 +71,25 @@ impl<'a> From<(&'a Scenario, &'a dyn TestCase)> for TestInfo {
 }
 
 #[derive(Debug)]
-pub struct TestSummaryListener {
-    test_summary: RefCell<TestSummary>,
-    start_time: Instant,
+struct ParseErrorInfo {
+    uri: String,
+    line: Option<u32>,
+    message: String,
 }
 
-impl TestSummaryListener {
-    pub fn new() -> TestSummaryListener {
-        TestSummaryListener {
-            test_summary: Default::default(),
-            start_time: Instant::now(),
-        }
-    }
-
-    pub fn print_test_summary(&self) {
-        let summary = self.test_summary.borrow();
-        print_test_summary(&summary, self.start_time);
-    }
+#[derive(Debug)]
+struct HookInfo {
+    hook_type: String,
+    location: String,
 }
 
-impl EventListener for TestSummaryListener {
-    fn on_event(&self, event: &Event<'_, '_>) {
-        if let Event::TestCaseFinished { scenario, test_case, ref result, .. } = *event {
-            self.test_summary.borrow_mut()
-                .add_result(scenario, test_case, result.status)
+impl<'a> From<&'a dyn HookTestStep<'_>> for HookInfo {
+    fn from(hook: &'a dyn HookTestStep<'_>) -> Self {
+        HookInfo {
+            hook_type: format!("{:?}", hook.get_hook_type()),
+            location: hook.get_glue_code_location()
+                .map(ToString::to_string)
+                .unwrap_or_default(),
         }
     }
 }
@@ -105,9 +117,27 @@ impl SyncTestSummaryListener {
 
 impl EventListener for SyncTestSummaryListener {
     fn on_event(&self, event: &Event<'_, '_>) {
-        if let Event::TestCaseFinished { scenario, test_case, ref result, .. } = *event {
-            self.test_summary.lock().unwrap().borrow_mut()
-                .add_result(scenario, test_case, result.status)
+        match *event {
+            Event::TestCaseFinished { scenario, test_case, ref result, will_be_retried: false, .. } => {
+                self.test_summary.lock().unwrap().borrow_mut()
+                    .add_result(scenario, test_case, result.status)
+            }
+            Event::FeatureHookFinished { hook, ref result, .. } |
+            Event::GlobalHookFinished { hook, ref result, .. } => {
+                self.test_summary.lock().unwrap().borrow_mut()
+                    .add_hook_result(hook, result.status)
+            }
+            Event::TestSourceParseError { uri, line, error, .. } => {
+                self.test_summary.lock().unwrap().borrow_mut().parse_errors.push(ParseErrorInfo {
+                    uri: uri.to_owned(),
+                    line,
+                    message: error.to_string(),
+                })
+            }
+            Event::SnippetSuggested { snippet, .. } => {
+                self.test_summary.lock().unwrap().borrow_mut().add_snippet(snippet)
+            }
+            _ => {}
         }
     }
 }
@@ -119,6 +149,7 @@ fn print_test_summary(summary: &TestSummary, start_time: Instant) {
 
     let tests_count = summary.passed
         + summary.skipped.len()
+        + summary.flaky.len()
         + summary.pending.len()
         + summary.undefined.len()
         + summary.ambiguous.len()
@@ -127,10 +158,14 @@ fn print_test_summary(summary: &TestSummary, start_time: Instant) {
     writeln!(&mut stdout, "Ran {} tests in {:?}", tests_count, time_elapsed).unwrap();
     write_passed(&mut stdout, summary.passed);
     write_test_infos(&mut stdout, &summary.skipped, "Skipped", Color::Yellow);
+    write_test_infos(&mut stdout, &summary.flaky, "Flaky", Color::Yellow);
     write_test_infos(&mut stdout, &summary.pending, "Pending", Color::Yellow);
     write_test_infos(&mut stdout, &summary.undefined, "Undefined", Color::Red);
     write_test_infos(&mut stdout, &summary.ambiguous, "Ambiguous", Color::Red);
     write_test_infos(&mut stdout, &summary.failed, "Failed", Color::Red);
+    write_hook_infos(&mut stdout, &summary.failed_hooks);
+    write_parse_error_infos(&mut stdout, &summary.parse_errors);
+    write_snippets(&mut stdout, &summary.snippets);
 
     writeln!(&mut stdout).unwrap();
 }
@@ -183,3 +218,67 @@ fn write_test_infos(stdout: &mut StandardStream, test_infos: &[TestInfo],
         stdout.set_color(ColorSpec::new().set_fg(None)).unwrap();
     }
 }
+
+fn write_hook_infos(stdout: &mut StandardStream, hook_infos: &[HookInfo]) {
+    if hook_infos.is_empty() {
+        return;
+    }
+
+    stdout.set_color(ColorSpec::new().set_fg(Some(Color::Red))).unwrap();
+    stdout.write_all(b"    Failed hooks: ").unwrap();
+    stdout.write_all(hook_infos.len().to_string().as_bytes()).unwrap();
+
+    for hook_info in hook_infos {
+        stdout.write_all(b"\n").unwrap();
+        stdout.write_all(b"        ").unwrap();
+        stdout.write_all(hook_info.hook_type.as_bytes()).unwrap();
+        stdout.write_all(b" \x1B[90m").unwrap();
+        stdout.write_all(b"# ").unwrap();
+        stdout.write_all(hook_info.location.as_bytes()).unwrap();
+        stdout.set_color(ColorSpec::new().set_fg(Some(Color::Red))).unwrap();
+    }
+
+    stdout.write_all(b"\n").unwrap();
+    stdout.set_color(ColorSpec::new().set_fg(None)).unwrap();
+}
+
+fn write_parse_error_infos(stdout: &mut StandardStream, parse_error_infos: &[ParseErrorInfo]) {
+    if parse_error_infos.is_empty() {
+        return;
+    }
+
+    stdout.set_color(ColorSpec::new().set_fg(Some(Color::Red))).unwrap();
+    stdout.write_all(b"    Unparsable feature files: ").unwrap();
+    stdout.write_all(parse_error_infos.len().to_string().as_bytes()).unwrap();
+
+    for parse_error_info in parse_error_infos {
+        stdout.write_all(b"\n").unwrap();
+        stdout.write_all(b"        ").unwrap();
+        stdout.write_all(parse_error_info.uri.as_bytes()).unwrap();
+        if let Some(line) = parse_error_info.line {
+            stdout.write_all(b":").unwrap();
+            stdout.write_all(line.to_string().as_bytes()).unwrap();
+        }
+        stdout.write_all(b": ").unwrap();
+        stdout.write_all(parse_error_info.message.as_bytes()).unwrap();
+    }
+
+    stdout.write_all(b"\n").unwrap();
+    stdout.set_color(ColorSpec::new().set_fg(None)).unwrap();
+}
+
+fn write_snippets(stdout: &mut StandardStream, snippets: &[String]) {
+    if snippets.is_empty() {
+        return;
+    }
+
+    stdout.set_color(ColorSpec::new().set_fg(Some(Color::Yellow))).unwrap();
+    stdout.write_all(b"\nYou can implement the undefined steps with these snippets:\n").unwrap();
+
+    for snippet in snippets {
+        stdout.write_all(b"\n").unwrap();
+        stdout.write_all(snippet.as_bytes()).unwrap();
+    }
+
+    stdout.set_color(ColorSpec::new().set_fg(None)).unwrap();
+}
@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use walkdir::WalkDir;
+
+use crate::Config;
+use crate::error::{Error, Result};
+use crate::glue::EmbeddedFeatureFile;
+use crate::runtime::SourceError;
+
+/// Finds the feature files of the features directory and the additional feature paths
+/// (or the embedded feature files) whose uris match the include and exclude globs.
+#[derive(Debug)]
+pub struct FeatureFiles<'c> {
+    features_dir: &'c Path,
+    feature_paths: &'c [&'c Path],
+    embedded_features: Option<&'c [EmbeddedFeatureFile]>,
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+#[derive(Debug)]
+pub struct FeatureFile {
+    pub uri: String,
+    pub source: FeatureSource,
+}
+
+#[derive(Debug)]
+pub enum FeatureSource {
+    /// The feature file is read at runtime.
+    Path(PathBuf),
+    /// The feature file was embedded into the test binary.
+    Embedded(&'static str),
+}
+
+impl<'c> FeatureFiles<'c> {
+    pub fn new(config: &Config<'c>) -> Result<FeatureFiles<'c>> {
+        let include = if config.include.is_empty() {
+            None
+        } else {
+            Some(glob_set(config.include)?)
+        };
+
+        Ok(FeatureFiles {
+            features_dir: config.features_dir,
+            feature_paths: config.feature_paths,
+            embedded_features: config.embedded_features,
+            include,
+            exclude: glob_set(config.exclude)?,
+        })
+    }
+
+    /// Returns the selected feature files in the order in which they were found.
+    ///
+    /// Directories that cannot be read are added to the source errors.
+    pub fn find(&self, source_errors: &mut Vec<SourceError>) -> Vec<FeatureFile> {
+        if let Some(embedded_features) = self.embedded_features {
+            return embedded_features.iter()
+                .filter(|embedded_feature| self.is_included(embedded_feature.uri))
+                .map(|embedded_feature| FeatureFile {
+                    uri: embedded_feature.uri.to_owned(),
+                    source: FeatureSource::Embedded(embedded_feature.source),
+                })
+                .collect();
+        }
+
+        let mut paths = Vec::new();
+        let mut found_paths = HashSet::new();
+
+        for root in iter::once(self.features_dir).chain(self.feature_paths.iter().copied()) {
+            for entry in WalkDir::new(root).follow_links(true) {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(err) => {
+                        let uri = err.path()
+                            .map(|path| self.uri(path))
+                            .unwrap_or_default();
+                        source_errors.push(SourceError::new(uri, None, Error::from(err)));
+                        continue;
+                    }
+                };
+
+                // individual files are used even if they do not end with ".feature"
+                let is_feature_file = entry.file_type().is_file()
+                    && (entry.depth() == 0 || entry.file_name().to_string_lossy().ends_with(".feature"));
+
+                if !is_feature_file {
+                    continue;
+                }
+
+                // the same file may be found through different paths, like `features/../features`
+                let canonical_path = fs::canonicalize(entry.path())
+                    .unwrap_or_else(|_| entry.path().to_path_buf());
+                if found_paths.insert(canonical_path) {
+                    paths.push(entry.into_path());
+                }
+            }
+        }
+
+        let uris = unambiguous_uris(&paths, |path| self.uri(path));
+
+        paths.into_iter()
+            .zip(uris)
+            .filter(|(_, uri)| self.is_included(uri))
+            .map(|(path, uri)| FeatureFile {
+                uri,
+                source: FeatureSource::Path(path),
+            })
+            .collect()
+    }
+
+    fn is_included(&self, uri: &str) -> bool {
+        self.include.as_ref().map_or(true, |include| include.is_match(uri))
+            && !self.exclude.is_match(uri)
+    }
+
+    /// The uri of a feature file is its path relative to the features directory.
+    ///
+    /// Feature files of other feature paths are relative to the current directory
+    /// (the directory of the tested crate with cargo) if possible.
+    fn uri(&self, path: &Path) -> String {
+        if let Ok(relative_path) = path.strip_prefix(self.features_dir) {
+            return relative_path.display().to_string();
+        }
+
+        let current_dir = env::current_dir().unwrap_or_default();
+        path.strip_prefix(&current_dir).unwrap_or(path).display().to_string()
+    }
+}
+
+/// Returns the uri of every path, but with the full path instead of uris that are shared
+/// by several files (e.g. `a.feature` in both the features directory and the current directory).
+fn unambiguous_uris<F>(paths: &[PathBuf], uri: F) -> Vec<String>
+    where F: Fn(&Path) -> String
+{
+    let uris = paths.iter()
+        .map(|path| uri(path))
+        .collect::<Vec<String>>();
+
+    let mut uri_counts = HashMap::with_capacity(uris.len());
+    for uri in &uris {
+        *uri_counts.entry(uri.clone()).or_insert(0) += 1;
+    }
+
+    paths.iter()
+        .zip(&uris)
+        .map(|(path, uri)| if uri_counts[uri] > 1 {
+            fs::canonicalize(path).unwrap_or_else(|_| path.clone()).display().to_string()
+        } else {
+            uri.clone()
+        })
+        .collect()
+}
+
+fn glob_set(patterns: &[&str]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|err| Error::InvalidFilter(format!("invalid glob \"{}\": {}", pattern, err)))?;
+        builder.add(glob);
+    }
+
+    builder.build()
+        .map_err(|err| Error::InvalidFilter(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_uris_with_globs() {
+        let globs = glob_set(&["wip/**", "*.draft.feature"]).unwrap();
+
+        assert!(globs.is_match("wip/a.feature"));
+        assert!(globs.is_match("wip/b/c.feature"));
+        assert!(globs.is_match("login.draft.feature"));
+        assert!(!globs.is_match("b/login.draft.feature"));
+        assert!(!globs.is_match("login.feature"));
+
+        assert!(glob_set(&["[a"]).is_err());
+    }
+
+    #[test]
+    fn replaces_shared_uris_with_full_paths() {
+        let paths = vec![
+            PathBuf::from("/features/a.feature"),
+            PathBuf::from("/features/b.feature"),
+            PathBuf::from("/shared/a.feature"),
+        ];
+
+        let uris = unambiguous_uris(&paths, |path| path.file_name().unwrap().to_string_lossy().into_owned());
+
+        assert_eq!(uris, vec!["/features/a.feature", "b.feature", "/shared/a.feature"]);
+    }
+}
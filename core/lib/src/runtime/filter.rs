@@ -1,17 +1,95 @@
-use gherkin::cuke::Cuke;
+use std::collections::HashSet;
+use std::path::Path;
+
+use gherkin::cuke::{Cuke, Location, Tag};
 
 use crate::Config;
+use crate::error::{Error, Result};
 use crate::glue::filter::CukePredicate;
+use crate::glue::filter::line::LinePredicate;
+use crate::glue::filter::name::NamePredicate;
 use crate::glue::filter::tag::TagPredicate;
 
 pub struct Filters<'f> {
     filters: Vec<CukePredicate<'f>>,
+    /// The feature files that are selected by line filters.
+    /// All feature files are selected if there are no line filters.
+    uris: HashSet<&'f str>,
+    /// Whether no scenario is selected, because the rerun file and the line filters are empty.
+    none: bool,
+    /// A tag that the tag expressions do not see, like `@ignore` for the test harness
+    /// that decides itself whether ignored scenarios are run.
+    ignored_tag: Option<&'f str>,
 }
 
 impl<'f> Filters<'f> {
+    /// Creates the filters of the config and of the content of its rerun file.
+    pub fn new(config: &'f Config<'f>, rerun_file_content: Option<&'f str>) -> Result<Self> {
+        let mut filters = Vec::with_capacity(config.tags.len() + config.tag_predicates.len() + 2);
+
+        for tag in config.tags {
+            let tag_predicate = TagPredicate::new(tag)
+                .map_err(Error::InvalidFilter)?;
+            filters.push(CukePredicate::Tag(tag_predicate));
+        }
+        filters.extend(config.tag_predicates.iter().cloned().map(CukePredicate::Tag));
+
+        if !config.names.is_empty() {
+            let name_predicate = NamePredicate::new(config.names)
+                .map_err(Error::InvalidFilter)?;
+            filters.push(CukePredicate::Name(name_predicate));
+        }
+
+        let mut line_selectors = config.lines.iter()
+            .map(|selector| relative_to_features_dir(selector, config.features_dir))
+            .collect::<Vec<&str>>();
+        if let Some(rerun_file_content) = rerun_file_content {
+            line_selectors.extend(rerun_file_content.split_whitespace());
+        }
+
+        // an empty rerun file means that the previous run had no failed scenarios
+        let none = rerun_file_content.is_some() && line_selectors.is_empty();
+
+        let mut uris = HashSet::new();
+        if !line_selectors.is_empty() {
+            let line_predicate = LinePredicate::parse(&line_selectors)
+                .map_err(Error::InvalidFilter)?;
+            uris.extend(line_predicate.uris());
+            filters.push(CukePredicate::Line(line_predicate));
+        }
+
+        Ok(Filters {
+            filters,
+            uris,
+            none,
+            ignored_tag: None,
+        })
+    }
+
+    /// Applies the tag expressions as if the scenarios were not tagged with the tag.
+    pub fn ignoring_tag(mut self, tag: &'f str) -> Self {
+        self.ignored_tag = Some(tag);
+        self
+    }
+
     pub fn apply(&self, uri: &str, cuke: &Cuke<'_>) -> bool {
+        if self.none {
+            return false;
+        }
+
+        if !self.uris.is_empty() && !self.uris.contains(uri) {
+            return false;
+        }
+
         for filter in &self.filters {
-            if !filter.apply(uri, cuke) {
+            let matches = match (filter, self.ignored_tag) {
+                (CukePredicate::Tag(tag_predicate), Some(ignored_tag)) => {
+                    tag_predicate.apply(&tags_without(&cuke.tags, ignored_tag))
+                }
+                _ => filter.apply(uri, cuke),
+            };
+
+            if !matches {
                 return false;
             }
         }
@@ -20,18 +98,87 @@ impl<'f> Filters<'f> {
     }
 }
 
-impl<'f> From<&'f Config<'f>> for Filters<'f> {
-    fn from(config: &'f Config<'f>) -> Self {
-        let mut filters = Vec::with_capacity(config.tags.len());
+fn tags_without<'t>(tags: &[Tag<'t>], tag_name: &str) -> Vec<Tag<'t>> {
+    tags.iter()
+        .filter(|tag| tag.name != tag_name)
+        .map(|tag| Tag {
+            location: Location {
+                line: tag.location.line,
+                column: tag.location.column,
+            },
+            name: tag.name,
+            ast_node_id: tag.ast_node_id,
+        })
+        .collect()
+}
 
-        for tag in config.tags {
-            let tag_predicate = TagPredicate::new(tag)
-                .expect("invalid tag filter");
-            filters.push(CukePredicate::Tag(tag_predicate));
+/// Strips the features directory (or its trailing part) from the path of a line selector.
+///
+/// This way `tests/features/to.feature:12` and `/full/path/tests/features/to.feature:12`
+/// both select the uri `to.feature` if the features directory is `/full/path/tests/features`.
+fn relative_to_features_dir<'s>(selector: &'s str, features_dir: &Path) -> &'s str {
+    let selector_path = Path::new(selector);
+
+    for ancestor in selector_path.ancestors() {
+        if ancestor.as_os_str().is_empty() || !features_dir.ends_with(ancestor) {
+            continue;
         }
 
-        Filters {
-            filters,
+        if let Some(relative_selector) = selector_path.strip_prefix(ancestor).ok().and_then(Path::to_str) {
+            return relative_selector;
+        }
+    }
+
+    selector
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_features_dir_from_line_selectors() {
+        let features_dir = Path::new("/full/path/tests/features");
+
+        assert_eq!(relative_to_features_dir("to.feature:12", features_dir), "to.feature:12");
+        assert_eq!(relative_to_features_dir("tests/features/to.feature:12", features_dir), "to.feature:12");
+        assert_eq!(relative_to_features_dir("/full/path/tests/features/a/to.feature:12:30", features_dir),
+            "a/to.feature:12:30");
+        assert_eq!(relative_to_features_dir("other/to.feature:12", features_dir), "other/to.feature:12");
+    }
+
+    fn tag(name: &str) -> Tag<'_> {
+        Tag {
+            location: Location {
+                line: 1,
+                column: 1,
+            },
+            name,
+            ast_node_id: "test",
         }
     }
+
+    #[test]
+    fn tag_expressions_do_not_see_the_ignored_tag() {
+        let tags = vec![tag("@ignore"), tag("@slow")];
+        let tag_predicate = TagPredicate::new("not @ignore and @slow").unwrap();
+
+        assert!(!tag_predicate.apply(&tags));
+        assert!(tag_predicate.apply(&tags_without(&tags, "@ignore")));
+    }
+
+    #[test]
+    fn empty_rerun_file_selects_no_scenarios() {
+        let config = Config::builder()
+            .features_dir("tests/features")
+            .build()
+            .unwrap();
+
+        config.with_config(|config| {
+            assert!(Filters::new(&config, Some("")).unwrap().none);
+            assert!(Filters::new(&config, Some("\n")).unwrap().none);
+            assert!(!Filters::new(&config, Some("to.feature:12\n")).unwrap().none);
+            assert!(!Filters::new(&config, None).unwrap().none);
+        });
+    }
 }
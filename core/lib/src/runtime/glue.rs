@@ -1,21 +1,51 @@
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Arc, RwLock};
 
 use gherkin::cuke;
+use regex::{RegexSet, RegexSetBuilder};
 
 use crate::glue::StaticGlueDefinitions;
+use crate::glue::step::parameter_type::StaticParameterTypeDef;
 use crate::runtime::{
-    AmbiguousCukeStepDefinitionMatch, HookDefinition, CukeStepDefinitionMatch,
+    AmbiguousCukeStepDefinitionMatch, FeatureHookDefinition, GlobalHookDefinition, HookDefinition,
+    CukeStepDefinitionMatch,
     StepDefinition, StepDefinitionMatch, UndefinedCukeStepDefinitionMatch,
 };
 use std::path::PathBuf;
 
+/// The size limit of the compiled regex set of all step definitions.
+///
+/// Each step definition regex was already compiled within the default size limit of 10 MB,
+/// but combined they may exceed it for large glue code.
+const STEP_DEFINITION_REGEX_SET_SIZE_LIMIT: usize = 100 * (1 << 20);
+
 #[derive(Debug)]
 pub struct Glue {
+    before_all_hooks: Vec<GlobalHookDefinition>,
+    before_feature_hooks: Vec<FeatureHookDefinition>,
     before_scenario_hooks: Vec<HookDefinition>,
     before_step_hooks: Vec<HookDefinition>,
-    step_definitions_by_pattern: HashMap<&'static str, StepDefinition>,
+    /// The step definitions ordered by their location,
+    /// shared with the step definition matches.
+    step_definitions: Vec<Arc<StepDefinition>>,
+    /// The regexes of the step definitions (in the same order)
+    /// to find all step definitions that match a step text at once.
+    ///
+    /// `None` if the combined regexes exceed the size limit,
+    /// then the regexes of the step definitions are matched one after another.
+    step_definition_regex_set: Option<RegexSet>,
+    /// The indices of the step definitions that match a step text,
+    /// scenario outlines and backgrounds repeat the same step texts across scenarios.
+    ///
+    /// Only the indices are cached, because the matched arguments borrow the doc string
+    /// or data table of each step, while the indices only depend on the step text.
+    step_definition_match_cache: RwLock<HashMap<String, Vec<usize>>>,
     after_step_hooks: Vec<HookDefinition>,
     after_scenario_hooks: Vec<HookDefinition>,
+    after_feature_hooks: Vec<FeatureHookDefinition>,
+    after_all_hooks: Vec<GlobalHookDefinition>,
 }
 
 #[doc(hidden)]
@@ -23,56 +53,166 @@ impl From<(PathBuf, &[StaticGlueDefinitions])> for Glue {
     fn from((base_path, static_glue_definitions): (PathBuf, &[StaticGlueDefinitions])) -> Glue {
         let base_path = base_path.as_path();
 
-        let before_scenario_hooks = static_glue_definitions.iter()
+        let mut before_all_hooks: Vec<GlobalHookDefinition> = static_glue_definitions.iter()
+            .flat_map(|glue| glue.before_all_hooks.iter())
+            .map(|static_hook_def| GlobalHookDefinition::from((base_path, static_hook_def)))
+            .collect();
+        let mut before_feature_hooks: Vec<FeatureHookDefinition> = static_glue_definitions.iter()
+            .flat_map(|glue| glue.before_feature_hooks.iter())
+            .map(|static_hook_def| FeatureHookDefinition::from((base_path, static_hook_def)))
+            .collect();
+        let mut before_scenario_hooks: Vec<HookDefinition> = static_glue_definitions.iter()
             .flat_map(|glue| glue.before_scenario_hooks.iter())
             .map(|static_hook_def| HookDefinition::from((base_path, static_hook_def)))
             .collect();
-        let before_step_hooks = static_glue_definitions.iter()
+        let mut before_step_hooks: Vec<HookDefinition> = static_glue_definitions.iter()
             .flat_map(|glue| glue.before_step_hooks.iter())
             .map(|static_hook_def| HookDefinition::from((base_path, static_hook_def)))
             .collect();
-        let after_step_hooks = static_glue_definitions.iter()
+        let mut after_step_hooks: Vec<HookDefinition> = static_glue_definitions.iter()
             .flat_map(|glue| glue.after_step_hooks.iter())
             .map(|static_hook_def| HookDefinition::from((base_path, static_hook_def)))
             .collect();
-        let after_scenario_hooks = static_glue_definitions.iter()
+        let mut after_scenario_hooks: Vec<HookDefinition> = static_glue_definitions.iter()
             .flat_map(|glue| glue.after_scenario_hooks.iter())
             .map(|static_hook_def| HookDefinition::from((base_path, static_hook_def)))
             .collect();
+        let mut after_feature_hooks: Vec<FeatureHookDefinition> = static_glue_definitions.iter()
+            .flat_map(|glue| glue.after_feature_hooks.iter())
+            .map(|static_hook_def| FeatureHookDefinition::from((base_path, static_hook_def)))
+            .collect();
+        let mut after_all_hooks: Vec<GlobalHookDefinition> = static_glue_definitions.iter()
+            .flat_map(|glue| glue.after_all_hooks.iter())
+            .map(|static_hook_def| GlobalHookDefinition::from((base_path, static_hook_def)))
+            .collect();
 
-        let step_definitions_capacity = static_glue_definitions.iter()
-            .flat_map(|glue| glue.steps.iter())
-            .count();
-        let mut step_definitions_by_pattern = HashMap::with_capacity(step_definitions_capacity);
-        static_glue_definitions.iter()
-            .flat_map(|glue| glue.steps.iter())
-            .map(|static_step_definition|
-                (static_step_definition.expression, StepDefinition::from((base_path, static_step_definition))))
-            .for_each(|(expression, step_definition)| {
-                let new_location = step_definition.location.clone();
-
-                if let Some(prev) = step_definitions_by_pattern.insert(expression, step_definition) {
-                    let prev_location = prev.location;
-                    panic!("duplicate step definition \"{}\":
-   first: {}
-  second: {}", prev.expression.regex.as_str(), prev_location, new_location)
-                }
-            });
+        sort_before_hooks(&mut before_all_hooks);
+        sort_before_hooks(&mut before_feature_hooks);
+        sort_before_hooks(&mut before_scenario_hooks);
+        sort_before_hooks(&mut before_step_hooks);
+        sort_after_hooks(&mut after_step_hooks);
+        sort_after_hooks(&mut after_scenario_hooks);
+        sort_after_hooks(&mut after_feature_hooks);
+        sort_after_hooks(&mut after_all_hooks);
+
+        let mut parameter_types: Vec<&'static StaticParameterTypeDef> = Vec::new();
+        for &parameter_type in static_glue_definitions.iter().flat_map(|glue| glue.parameter_types.iter()) {
+            if let Some(prev) = parameter_types.iter().find(|prev| prev.name == parameter_type.name) {
+                panic!("{}", duplicate_message("parameter type", parameter_type.name,
+                    &prev.type_name, &parameter_type.type_name))
+            }
+            parameter_types.push(parameter_type);
+        }
+
+        let mut step_definitions: Vec<StepDefinition> = Vec::new();
+        let mut step_definition_indices_by_pattern = HashMap::new();
+        for static_step_definition in static_glue_definitions.iter().flat_map(|glue| glue.steps.iter()) {
+            let step_definition = StepDefinition::from((base_path, static_step_definition, &parameter_types[..]));
+
+            let expression = static_step_definition.expression;
+            if let Some(&prev_index) = step_definition_indices_by_pattern.get(expression) {
+                let prev: &StepDefinition = &step_definitions[prev_index];
+                panic!("{}", duplicate_message("step definition", expression,
+                    &prev.location, &step_definition.location))
+            }
+
+            step_definition_indices_by_pattern.insert(expression, step_definitions.len());
+            step_definitions.push(step_definition);
+        }
+
+        // the order of the step definitions is the order of the candidates of ambiguous steps
+        step_definitions.sort_by(|a, b| {
+            let a = a.get_location();
+            let b = b.get_location();
+            (a.file_path(), a.line_number()).cmp(&(b.file_path(), b.line_number()))
+        });
+        let step_definition_regex_set = RegexSetBuilder::new(step_definitions.iter()
+            .map(|step_definition| step_definition.expression.regex.as_str()))
+            .size_limit(STEP_DEFINITION_REGEX_SET_SIZE_LIMIT)
+            .build()
+            .ok();
+        let step_definitions = step_definitions.into_iter()
+            .map(Arc::new)
+            .collect();
 
         Glue {
+            before_all_hooks,
+            before_feature_hooks,
             before_scenario_hooks,
             before_step_hooks,
-            step_definitions_by_pattern,
+            step_definitions,
+            step_definition_regex_set,
+            step_definition_match_cache: RwLock::new(HashMap::new()),
             after_step_hooks,
             after_scenario_hooks,
+            after_feature_hooks,
+            after_all_hooks,
         }
     }
 }
 
+/// A hook definition that is executed in a defined order.
+trait OrderedHook {
+    fn get_order(&self) -> isize;
+}
+
+impl OrderedHook for HookDefinition {
+    fn get_order(&self) -> isize {
+        HookDefinition::get_order(self)
+    }
+}
+
+impl OrderedHook for FeatureHookDefinition {
+    fn get_order(&self) -> isize {
+        FeatureHookDefinition::get_order(self)
+    }
+}
+
+impl OrderedHook for GlobalHookDefinition {
+    fn get_order(&self) -> isize {
+        GlobalHookDefinition::get_order(self)
+    }
+}
+
+/// The panic message of a glue definition that is defined twice.
+fn duplicate_message(kind: &str, name: &str, first: &dyn Display, second: &dyn Display) -> String {
+    format!("duplicate {} \"{}\":\n   first: {}\n  second: {}", kind, name, first, second)
+}
+
+/// Sorts before hooks in ascending order.
+///
+/// The sort is stable, so hooks with the same order keep their declaration order.
+fn sort_before_hooks<H: OrderedHook>(hooks: &mut [H]) {
+    hooks.sort_by_key(|hook| hook.get_order());
+}
+
+/// Sorts after hooks in descending order.
+///
+/// The sort is stable, so hooks with the same order keep their declaration order.
+fn sort_after_hooks<H: OrderedHook>(hooks: &mut [H]) {
+    hooks.sort_by_key(|hook| Reverse(hook.get_order()));
+}
+
 #[doc(hidden)]
 impl Glue {
-    pub fn get_step_definitions_by_pattern(&self) -> &HashMap<&'static str, StepDefinition> {
-        &self.step_definitions_by_pattern
+    /// Returns the step definitions ordered by their location.
+    pub fn get_step_definitions(&self) -> &[Arc<StepDefinition>] {
+        &self.step_definitions
+    }
+
+    /// Returns the step definitions by their expression.
+    pub fn get_step_definitions_by_pattern(&self) -> HashMap<&str, &StepDefinition> {
+        self.step_definitions.iter()
+            .map(|step_definition| (step_definition.get_pattern(), &**step_definition))
+            .collect()
+    }
+
+    pub fn get_before_all_hooks(&self) -> &Vec<GlobalHookDefinition> {
+        &self.before_all_hooks
+    }
+
+    pub fn get_before_feature_hooks(&self) -> &Vec<FeatureHookDefinition> {
+        &self.before_feature_hooks
     }
 
     pub fn get_before_scenario_hooks(&self) -> &Vec<HookDefinition> {
@@ -91,23 +231,29 @@ impl Glue {
         &self.after_scenario_hooks
     }
 
+    pub fn get_after_feature_hooks(&self) -> &Vec<FeatureHookDefinition> {
+        &self.after_feature_hooks
+    }
+
+    pub fn get_after_all_hooks(&self) -> &Vec<GlobalHookDefinition> {
+        &self.after_all_hooks
+    }
+
     pub fn step_definition_match<'s, 'a: 's>(&'a self, feature_path: &str, step: &'s cuke::Step<'_>)
         -> StepDefinitionMatch<'s> {
 
-        let mut matches = Vec::new();
-
-        for step_definition in self.step_definitions_by_pattern.values() {
-            let arguments = step_definition.matched_arguments(&step);
+        let mut matches = self.matching_step_definition_indices(&step.text).into_iter()
+            .filter_map(|index| {
+                let step_definition = &self.step_definitions[index];
 
-            if let Some(arguments) = arguments {
-                matches.push(CukeStepDefinitionMatch {
+                step_definition.matched_arguments(&step).map(|arguments| CukeStepDefinitionMatch {
                     arguments,
-                    step_definition: step_definition.clone(),
+                    step_definition: Arc::clone(step_definition),
                     feature_path: feature_path.to_owned(),
                     step,
-                });
-            }
-        }
+                })
+            })
+            .collect::<Vec<CukeStepDefinitionMatch<'s>>>();
 
         if matches.is_empty() {
             return StepDefinitionMatch::Undefined(UndefinedCukeStepDefinitionMatch {
@@ -120,6 +266,7 @@ impl Glue {
                 feature_path: feature_path.to_owned(),
                 step,
                 arguments: Vec::new(),
+                candidates: matches,
             });
         }
 
@@ -127,4 +274,325 @@ impl Glue {
         StepDefinitionMatch::Cuke(step_definition_match)
     }
 
+    /// Returns the indices of the step definitions whose regexes match the step text.
+    fn matching_step_definition_indices(&self, text: &str) -> Vec<usize> {
+        if let Some(indices) = self.step_definition_match_cache.read().unwrap().get(text) {
+            return indices.clone();
+        }
+
+        let indices = match self.step_definition_regex_set {
+            Some(ref step_definition_regex_set) => step_definition_regex_set.matches(text).into_iter()
+                .collect::<Vec<usize>>(),
+            None => self.step_definitions.iter()
+                .enumerate()
+                .filter(|(_, step_definition)| step_definition.expression.regex.is_match(text))
+                .map(|(index, _)| index)
+                .collect::<Vec<usize>>(),
+        };
+        self.step_definition_match_cache.write().unwrap()
+            .insert(text.to_owned(), indices.clone());
+
+        indices
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::glue::StaticGlueDefinitions;
+    use crate::glue::error::ExecutionError;
+    use crate::glue::feature::Feature;
+    use crate::glue::global::Global;
+    use crate::glue::hook::{StaticFeatureHookDef, StaticGlobalHookDef, StaticHookDef};
+    use crate::glue::location::StaticGlueCodeLocation;
+    use crate::glue::scenario::Scenario;
+    use crate::glue::step::{ExpressionType, StaticStepDef, StepKeyword};
+    use crate::glue::step::argument::StepArgument;
+
+    use super::*;
+
+    fn hook_fn(_scenario: &mut Scenario) -> ::std::result::Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    static FIRST_MODULE_HOOKS: &[&StaticHookDef] = &[
+        &StaticHookDef {
+            name: "first_module_late_hook",
+            order: 10,
+            tag_expression: "",
+            timeout: None,
+            hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/first.rs", line: 1 },
+        },
+        &StaticHookDef {
+            name: "first_module_default_hook",
+            order: 0,
+            tag_expression: "",
+            timeout: None,
+            hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/first.rs", line: 2 },
+        },
+    ];
+
+    static SECOND_MODULE_HOOKS: &[&StaticHookDef] = &[
+        &StaticHookDef {
+            name: "second_module_early_hook",
+            order: -5,
+            tag_expression: "",
+            timeout: None,
+            hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/second.rs", line: 3 },
+        },
+        &StaticHookDef {
+            name: "second_module_default_hook",
+            order: 0,
+            tag_expression: "",
+            timeout: None,
+            hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/second.rs", line: 4 },
+        },
+    ];
+
+    fn feature_hook_fn(_feature: &mut Feature) -> ::std::result::Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    static FIRST_MODULE_FEATURE_HOOKS: &[&StaticFeatureHookDef] = &[
+        &StaticFeatureHookDef {
+            name: "first_module_late_feature_hook",
+            order: 10,
+            tag_expression: "",
+            hook_fn: feature_hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/first.rs", line: 1 },
+        },
+        &StaticFeatureHookDef {
+            name: "first_module_default_feature_hook",
+            order: 0,
+            tag_expression: "",
+            hook_fn: feature_hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/first.rs", line: 2 },
+        },
+    ];
+
+    static SECOND_MODULE_FEATURE_HOOKS: &[&StaticFeatureHookDef] = &[
+        &StaticFeatureHookDef {
+            name: "second_module_early_feature_hook",
+            order: -5,
+            tag_expression: "",
+            hook_fn: feature_hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/second.rs", line: 3 },
+        },
+        &StaticFeatureHookDef {
+            name: "second_module_default_feature_hook",
+            order: 0,
+            tag_expression: "",
+            hook_fn: feature_hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/second.rs", line: 4 },
+        },
+    ];
+
+    fn global_hook_fn(_global: &mut Global) -> ::std::result::Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    static FIRST_MODULE_GLOBAL_HOOKS: &[&StaticGlobalHookDef] = &[
+        &StaticGlobalHookDef {
+            name: "first_module_late_global_hook",
+            order: 10,
+            hook_fn: global_hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/first.rs", line: 1 },
+        },
+        &StaticGlobalHookDef {
+            name: "first_module_default_global_hook",
+            order: 0,
+            hook_fn: global_hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/first.rs", line: 2 },
+        },
+    ];
+
+    static SECOND_MODULE_GLOBAL_HOOKS: &[&StaticGlobalHookDef] = &[
+        &StaticGlobalHookDef {
+            name: "second_module_early_global_hook",
+            order: -5,
+            hook_fn: global_hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/second.rs", line: 3 },
+        },
+        &StaticGlobalHookDef {
+            name: "second_module_default_global_hook",
+            order: 0,
+            hook_fn: global_hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/second.rs", line: 4 },
+        },
+    ];
+
+    fn step_fn(_scenario: &mut Scenario, _arguments: &[StepArgument<'_>])
+        -> ::std::result::Result<(), ExecutionError>
+    {
+        Ok(())
+    }
+
+    static STEPS: &[&StaticStepDef] = &[
+        &StaticStepDef {
+            name: "press_digit",
+            keyword: StepKeyword::When,
+            expression: "^I press (\\d+)$",
+            expression_type: ExpressionType::Regex,
+            parameter_types: &["u32"],
+            timeout: None,
+            step_fn,
+            step_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/second.rs", line: 5 },
+        },
+        &StaticStepDef {
+            name: "press",
+            keyword: StepKeyword::When,
+            expression: "^I press (.+)$",
+            expression_type: ExpressionType::Regex,
+            parameter_types: &["&str"],
+            timeout: None,
+            step_fn,
+            step_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/first.rs", line: 7 },
+        },
+        &StaticStepDef {
+            name: "result",
+            keyword: StepKeyword::Then,
+            expression: "the result is {int}",
+            expression_type: ExpressionType::CucumberExpression,
+            parameter_types: &["i32"],
+            timeout: None,
+            step_fn,
+            step_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/first.rs", line: 3 },
+        },
+    ];
+
+    fn glue() -> Glue {
+        let static_glue_definitions = &[
+            StaticGlueDefinitions {
+                before_all_hooks: FIRST_MODULE_GLOBAL_HOOKS,
+                before_feature_hooks: FIRST_MODULE_FEATURE_HOOKS,
+                before_scenario_hooks: FIRST_MODULE_HOOKS,
+                before_step_hooks: FIRST_MODULE_HOOKS,
+                steps: &[],
+                parameter_types: &[],
+                after_step_hooks: FIRST_MODULE_HOOKS,
+                after_scenario_hooks: FIRST_MODULE_HOOKS,
+                after_feature_hooks: FIRST_MODULE_FEATURE_HOOKS,
+                after_all_hooks: FIRST_MODULE_GLOBAL_HOOKS,
+            },
+            StaticGlueDefinitions {
+                before_all_hooks: SECOND_MODULE_GLOBAL_HOOKS,
+                before_feature_hooks: SECOND_MODULE_FEATURE_HOOKS,
+                before_scenario_hooks: SECOND_MODULE_HOOKS,
+                before_step_hooks: SECOND_MODULE_HOOKS,
+                steps: &[],
+                parameter_types: &[],
+                after_step_hooks: SECOND_MODULE_HOOKS,
+                after_scenario_hooks: SECOND_MODULE_HOOKS,
+                after_feature_hooks: SECOND_MODULE_FEATURE_HOOKS,
+                after_all_hooks: SECOND_MODULE_GLOBAL_HOOKS,
+            },
+        ];
+
+        Glue::from((PathBuf::from("/crate/tests"), &static_glue_definitions[..]))
+    }
+
+    fn lines(hooks: &[HookDefinition]) -> Vec<u32> {
+        hooks.iter()
+            .map(|hook| hook.get_location().line_number())
+            .collect()
+    }
+
+    fn feature_lines(hooks: &[FeatureHookDefinition]) -> Vec<u32> {
+        hooks.iter()
+            .map(|hook| hook.get_location().line_number())
+            .collect()
+    }
+
+    fn global_lines(hooks: &[GlobalHookDefinition]) -> Vec<u32> {
+        hooks.iter()
+            .map(|hook| hook.get_location().line_number())
+            .collect()
+    }
+
+    #[test]
+    fn before_hooks_are_sorted_ascending_across_modules() {
+        let glue = glue();
+
+        assert_eq!(lines(glue.get_before_scenario_hooks()), vec![3, 2, 4, 1]);
+        assert_eq!(lines(glue.get_before_step_hooks()), vec![3, 2, 4, 1]);
+        assert_eq!(feature_lines(glue.get_before_feature_hooks()), vec![3, 2, 4, 1]);
+        assert_eq!(global_lines(glue.get_before_all_hooks()), vec![3, 2, 4, 1]);
+    }
+
+    #[test]
+    fn after_hooks_are_sorted_descending_across_modules() {
+        let glue = glue();
+
+        assert_eq!(lines(glue.get_after_step_hooks()), vec![1, 2, 4, 3]);
+        assert_eq!(lines(glue.get_after_scenario_hooks()), vec![1, 2, 4, 3]);
+        assert_eq!(feature_lines(glue.get_after_feature_hooks()), vec![1, 2, 4, 3]);
+        assert_eq!(global_lines(glue.get_after_all_hooks()), vec![1, 2, 4, 3]);
+    }
+
+    #[test]
+    fn matches_step_definitions_in_location_order() {
+        let static_glue_definitions = &[
+            StaticGlueDefinitions {
+                before_all_hooks: &[],
+                before_feature_hooks: &[],
+                before_scenario_hooks: &[],
+                before_step_hooks: &[],
+                steps: STEPS,
+                parameter_types: &[],
+                after_step_hooks: &[],
+                after_scenario_hooks: &[],
+                after_feature_hooks: &[],
+                after_all_hooks: &[],
+            },
+        ];
+        let glue = Glue::from((PathBuf::from("/crate/tests"), &static_glue_definitions[..]));
+
+        let names = glue.get_step_definitions().iter()
+            .map(|step_definition| step_definition.get_name())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["result", "press", "press_digit"]);
+
+        let step_definitions_by_pattern = glue.get_step_definitions_by_pattern();
+        assert_eq!(step_definitions_by_pattern.len(), 3);
+        assert_eq!(step_definitions_by_pattern["^I press (\\d+)$"].get_name(), "press_digit");
+
+        assert_eq!(glue.matching_step_definition_indices("I press 1"), vec![1, 2]);
+        assert_eq!(glue.matching_step_definition_indices("I press +"), vec![1]);
+        assert_eq!(glue.matching_step_definition_indices("the result is -3"), vec![0]);
+        assert!(glue.matching_step_definition_indices("the result is three").is_empty());
+
+        // repeated step texts are matched with the cached indices
+        assert_eq!(glue.matching_step_definition_indices("I press 1"), vec![1, 2]);
+        assert_eq!(glue.step_definition_match_cache.read().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn matches_step_definitions_without_regex_set() {
+        let static_glue_definitions = &[
+            StaticGlueDefinitions {
+                before_all_hooks: &[],
+                before_feature_hooks: &[],
+                before_scenario_hooks: &[],
+                before_step_hooks: &[],
+                steps: STEPS,
+                parameter_types: &[],
+                after_step_hooks: &[],
+                after_scenario_hooks: &[],
+                after_feature_hooks: &[],
+                after_all_hooks: &[],
+            },
+        ];
+        let mut glue = Glue::from((PathBuf::from("/crate/tests"), &static_glue_definitions[..]));
+        glue.step_definition_regex_set = None;
+
+        assert_eq!(glue.matching_step_definition_indices("I press 1"), vec![1, 2]);
+        assert_eq!(glue.matching_step_definition_indices("the result is -3"), vec![0]);
+        assert!(glue.matching_step_definition_indices("the result is three").is_empty());
+    }
 }
@@ -1,19 +1,25 @@
 use std::fmt;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use gherkin::cuke::Tag;
 
 use crate::api::GlueCodeLocation;
 use crate::error::Result;
 use crate::glue::filter::tag::TagPredicate;
-use crate::glue::hook::{HookFn, StaticHookDef};
-use crate::runtime::Scenario;
+use crate::glue::feature::Feature;
+use crate::glue::global::Global;
+use crate::glue::location::StaticGlueCodeLocation;
+use crate::glue::hook::{
+    FeatureHookFn, GlobalHookFn, HookFn, StaticFeatureHookDef, StaticGlobalHookDef, StaticHookDef,
+};
+use crate::glue::scenario::Scenario;
 
 #[derive(Clone)]
 pub struct HookDefinition {
     tag_predicate: TagPredicate<'static>,
-    order: u32,
-    //    timeout: Option<Duration>,
+    order: isize,
+    timeout: Option<Duration>,
     hook_fn: HookFn,
     location: GlueCodeLocation,
 }
@@ -23,7 +29,7 @@ impl fmt::Debug for HookDefinition {
         f.debug_struct("HookDefinition")
             .field("tag_predicate", &self.tag_predicate)
             .field("order", &self.order)
-//            .field("timeout", &self.timeout)
+            .field("timeout", &self.timeout)
             .field("hook_fn", &"<hook_fn>")
             .field("location", &self.location)
             .finish()
@@ -39,32 +45,14 @@ impl From<(&Path, &&StaticHookDef)> for HookDefinition {
                     static_hook_def.tag_expression, err);
             });
 
-        let location = (static_hook_def.hook_fn_location_fn)();
-        let file_path = PathBuf::from(location.file);
-        let mut relative_file_path = file_path.as_path();
-        for ancestor in file_path.ancestors() {
-            if base_path.ends_with(ancestor) {
-                match file_path.strip_prefix(ancestor) {
-                    Ok(path) => {
-                        relative_file_path = path;
-                        break;
-                    },
-                    Err(_strip_prefix_error) => {
-                        panic!("unable to strip base path \"{}\" from path \"{}\"",
-                               base_path.display(), file_path.display());
-                    }
-                }
-            }
-        }
+        let location = relative_location(base_path, &(static_hook_def.hook_fn_location_fn)());
 
         HookDefinition {
             tag_predicate,
-            order: 0,
+            order: static_hook_def.order,
+            timeout: static_hook_def.timeout,
             hook_fn: static_hook_def.hook_fn,
-            location: GlueCodeLocation {
-                file_path: relative_file_path.to_owned(),
-                line_number: location.line,
-            },
+            location,
         }
     }
 }
@@ -74,8 +62,8 @@ impl HookDefinition {
         &self.location
     }
 
-    pub fn execute(&self, scenario: &mut Scenario<'_, '_>) -> Result<()> {
-        let result = (self.hook_fn)(&mut scenario.glue_scenario);
+    pub fn execute(&self, scenario: &mut Scenario) -> Result<()> {
+        let result = (self.hook_fn)(scenario);
         match result {
             Ok(_) => Ok(()),
             Err(error) => Err(crate::error::Error::Execution(error)),
@@ -86,7 +74,145 @@ impl HookDefinition {
         self.tag_predicate.apply(tags)
     }
 
-    pub fn get_order(&self) -> u32 {
+    pub fn get_order(&self) -> isize {
+        self.order
+    }
+
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+}
+
+#[derive(Clone)]
+pub struct GlobalHookDefinition {
+    order: isize,
+    hook_fn: GlobalHookFn,
+    location: GlueCodeLocation,
+}
+
+impl fmt::Debug for GlobalHookDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        f.debug_struct("GlobalHookDefinition")
+            .field("order", &self.order)
+            .field("hook_fn", &"<hook_fn>")
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+impl From<(&Path, &&StaticGlobalHookDef)> for GlobalHookDefinition {
+    fn from((base_path, static_hook_def): (&Path, &&StaticGlobalHookDef)) -> Self {
+        let location = relative_location(base_path, &(static_hook_def.hook_fn_location_fn)());
+
+        GlobalHookDefinition {
+            order: static_hook_def.order,
+            hook_fn: static_hook_def.hook_fn,
+            location,
+        }
+    }
+}
+
+impl GlobalHookDefinition {
+    pub fn get_location(&self) -> &GlueCodeLocation {
+        &self.location
+    }
+
+    pub fn execute(&self, global: &mut Global) -> Result<()> {
+        let result = (self.hook_fn)(global);
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(crate::error::Error::Execution(error)),
+        }
+    }
+
+    pub fn get_order(&self) -> isize {
         self.order
     }
 }
+
+#[derive(Clone)]
+pub struct FeatureHookDefinition {
+    tag_predicate: TagPredicate<'static>,
+    order: isize,
+    hook_fn: FeatureHookFn,
+    location: GlueCodeLocation,
+}
+
+impl fmt::Debug for FeatureHookDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        f.debug_struct("FeatureHookDefinition")
+            .field("tag_predicate", &self.tag_predicate)
+            .field("order", &self.order)
+            .field("hook_fn", &"<hook_fn>")
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+impl From<(&Path, &&StaticFeatureHookDef)> for FeatureHookDefinition {
+    fn from((base_path, static_hook_def): (&Path, &&StaticFeatureHookDef)) -> Self {
+        let tag_predicate = TagPredicate::new(static_hook_def.tag_expression)
+            .unwrap_or_else(|err| {
+                panic!("tag expression \"{}\"is invalid: {}\n\
+                        It should have been checked by codegen already, this is a bug!",
+                    static_hook_def.tag_expression, err);
+            });
+
+        let location = relative_location(base_path, &(static_hook_def.hook_fn_location_fn)());
+
+        FeatureHookDefinition {
+            tag_predicate,
+            order: static_hook_def.order,
+            hook_fn: static_hook_def.hook_fn,
+            location,
+        }
+    }
+}
+
+impl FeatureHookDefinition {
+    pub fn get_location(&self) -> &GlueCodeLocation {
+        &self.location
+    }
+
+    pub fn execute(&self, feature: &mut Feature) -> Result<()> {
+        let result = (self.hook_fn)(feature);
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(crate::error::Error::Execution(error)),
+        }
+    }
+
+    pub fn matches(&self, tags: &[Tag<'_>]) -> bool {
+        self.tag_predicate.apply(tags)
+    }
+
+    pub fn get_order(&self) -> isize {
+        self.order
+    }
+}
+
+/// Returns the location of glue code with the file path relative to the base path of the glue,
+/// the directory of the tested crate.
+pub(crate) fn relative_location(base_path: &Path, location: &StaticGlueCodeLocation) -> GlueCodeLocation {
+    let file_path = PathBuf::from(location.file);
+    let mut relative_file_path = file_path.as_path();
+    for ancestor in file_path.ancestors() {
+        if base_path.ends_with(ancestor) {
+            match file_path.strip_prefix(ancestor) {
+                Ok(path) => {
+                    relative_file_path = path;
+                    break;
+                },
+                Err(_strip_prefix_error) => {
+                    panic!("unable to strip base path \"{}\" from path \"{}\"",
+                           base_path.display(), file_path.display());
+                }
+            }
+        }
+    }
+
+    GlueCodeLocation {
+        file_path: relative_file_path.to_owned(),
+        line_number: location.line,
+    }
+}
@@ -1,20 +1,25 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fs;
-use std::time::SystemTime;
+use std::io;
+use std::time::{Instant, SystemTime};
 
 use gherkin::ast::GherkinDocument;
 use gherkin::{IdGenerator, IncrementingIdGenerator};
 use gherkin::cuke::Cuke;
 use rayon::prelude::*;
-use walkdir::{DirEntry, WalkDir};
+use rayon::{ThreadPool, ThreadPoolBuilder};
 
 use crate::{Config, ExecutionMode};
-use crate::runner::{EventBus, EventPublisher, Runner, SyncEventBus};
+use crate::config::SyncEventListenerRef;
+use crate::api::{GlueCheck, RunOutcome};
+use crate::error::Error;
+use crate::runner::{EventBus, EventPublisher, FeatureRun, Runner, SyncEventBus};
+use crate::runtime::feature_files::{FeatureFile, FeatureFiles, FeatureSource};
 use crate::runtime::filter::Filters;
 
 use crate::api::event::{Event, EventListener, SyncEventListener};
 
-use self::event_listener::{ExitStatusListener, SyncExitStatusListener, SyncTestSummaryListener, TestSummaryListener};
+use self::event_listener::{SyncRerunFileListener, SyncRunOutcomeListener};
 pub use self::glue::*;
 pub use self::hook_definition::*;
 pub use self::scenario::*;
@@ -31,22 +36,51 @@ pub mod test_case;
 mod scenario;
 mod step_definition_match;
 pub mod event_listener;
+mod feature_files;
 mod filter;
+pub(crate) mod snippet;
 
-pub fn run(glue: Glue, config: Config<'_>) -> i32 {
+pub fn run(glue: Glue, config: Config<'_>) -> crate::error::Result<RunOutcome> {
+    run_with_listener(glue, config, None)
+}
+
+/// Runs the cukes like `run`, but also notifies the given event listener,
+/// e.g. the listener that prints the test summary or the listener of the libtest compatible harness.
+pub(crate) fn run_with_listener(
+    glue: Glue,
+    config: Config<'_>,
+    event_listener: Option<&dyn SyncEventListener>,
+) -> crate::error::Result<RunOutcome>
+{
     cuke_runner_glue::panic::register_cuke_runner_hook();
 
-    let runner = Runner::new(glue, config.dry_run);
-    let filters = Filters::from(&config);
+    let start_instant = Instant::now();
+
+    let runner = Runner::new(glue, config.dry_run, config.step_timeout, config.retries);
+    let rerun_file_content = read_rerun_file(&config)?;
+    let filters = Filters::new(&config, rerun_file_content.as_deref())?;
+    let feature_files = FeatureFiles::new(&config)?;
 
-    let exit_status = match config.execution_mode {
+    let rerun_file_listener = SyncRerunFileListener::new();
+    let run_outcome_listener = SyncRunOutcomeListener::new(config.strict, config.fail_on_flaky);
+
+    let mut listeners: Vec<&dyn SyncEventListener> = Vec::with_capacity(3);
+    listeners.push(&rerun_file_listener);
+    listeners.push(&run_outcome_listener);
+    if let Some(event_listener) = event_listener {
+        listeners.push(event_listener);
+    }
+
+    match config.execution_mode {
         ExecutionMode::Sequential { event_listeners } => {
-            let exit_status_listener = ExitStatusListener::new();
-            let test_summary_listener = TestSummaryListener::new();
+            let listener_refs = listeners.into_iter()
+                .map(SyncEventListenerRef)
+                .collect::<Vec<SyncEventListenerRef<'_>>>();
 
-            let mut listeners: Vec<&dyn EventListener> = Vec::with_capacity(2 + event_listeners.len());
-            listeners.push(&exit_status_listener);
-            listeners.push(&test_summary_listener);
+            let mut listeners: Vec<&dyn EventListener> = Vec::with_capacity(listener_refs.len() + event_listeners.len());
+            for listener_ref in &listener_refs {
+                listeners.push(listener_ref);
+            }
 
             for event_listener in event_listeners {
                 listeners.push(*event_listener);
@@ -54,56 +88,130 @@ pub fn run(glue: Glue, config: Config<'_>) -> i32 {
 
             let event_bus = EventBus::new(listeners);
 
-            run_sequential(runner, filters, &event_bus, &config);
-
-            test_summary_listener.print_test_summary();
-            exit_status_listener.get_exit_status(config.strict)
+            run_sequential(runner, filters, &event_bus, &feature_files);
         }
         ExecutionMode::ParallelFeatures { event_listeners } => {
-            init_rayon();
-
-            let exit_status_listener = SyncExitStatusListener::new();
-            let test_summary_listener = SyncTestSummaryListener::new();
+            let thread_pool = build_thread_pool(config.threads)?;
 
-            let mut listeners: Vec<&dyn SyncEventListener> = Vec::with_capacity(2 + event_listeners.len());
-            listeners.push(&exit_status_listener);
-            listeners.push(&test_summary_listener);
+            listeners.extend(event_listeners.iter().copied());
+            let event_bus = SyncEventBus::new(listeners);
 
-            for event_listener in event_listeners {
-                listeners.push(*event_listener);
-            }
+            run_parallel_features(runner, filters, &event_bus, &feature_files, &thread_pool);
+        }
+        ExecutionMode::ParallelScenarios { event_listeners } => {
+            let thread_pool = build_thread_pool(config.threads)?;
 
+            listeners.extend(event_listeners.iter().copied());
             let event_bus = SyncEventBus::new(listeners);
 
-            run_parallel_features(runner, filters, &event_bus, &config);
-
-            test_summary_listener.print_test_summary();
-            exit_status_listener.get_exit_status(config.strict)
+            run_parallel_scenarios(runner, filters, &event_bus, &feature_files, &thread_pool);
         }
-        ExecutionMode::ParallelScenarios { event_listeners } => {
-            init_rayon();
+    }
 
-            let exit_status_listener = SyncExitStatusListener::new();
-            let test_summary_listener = SyncTestSummaryListener::new();
+    rerun_file_listener.write_rerun_file(config.output_dir)?;
 
-            let mut listeners: Vec<&dyn SyncEventListener> = Vec::with_capacity(2 + event_listeners.len());
-            listeners.push(&exit_status_listener);
-            listeners.push(&test_summary_listener);
+    Ok(run_outcome_listener.get_run_outcome(start_instant.elapsed()))
+}
 
-            for event_listener in event_listeners {
-                listeners.push(*event_listener);
-            }
+/// A cuke that passes the filters of a config.
+#[derive(Debug)]
+pub(crate) struct CukeInfo {
+    pub uri: String,
+    pub name: String,
+    /// The line of the example row for scenario outlines, otherwise the line of the scenario.
+    pub line: u32,
+    pub tags: Vec<String>,
+}
 
-            let event_bus = SyncEventBus::new(listeners);
+/// Lists the cukes that would be run with the config, without running them.
+///
+/// The tag expressions of the config are applied as if the cukes were not tagged with the ignored tag.
+pub(crate) fn list_cukes(config: &Config<'_>, ignored_tag: Option<&str>) -> crate::error::Result<Vec<CukeInfo>> {
+    let rerun_file_content = read_rerun_file(config)?;
+    let mut filters = Filters::new(config, rerun_file_content.as_deref())?;
+    if let Some(ignored_tag) = ignored_tag {
+        filters = filters.ignoring_tag(ignored_tag);
+    }
+    let feature_files = FeatureFiles::new(config)?;
+
+    let mut id_generator = IncrementingIdGenerator::new();
+    let mut source_errors = Vec::new();
+    let parsed_gherkin_documents = parse_gherking_documents(&feature_files, &mut id_generator, &mut source_errors);
+    fail_on_source_errors(source_errors)?;
+    let event_bus = EventBus::new(Vec::new());
+    let parsed_cukes = parse_cukes(&parsed_gherkin_documents, &event_bus, &mut id_generator);
+
+    let cukes = parsed_cukes.into_iter()
+        .filter(|parsed_cuke| filters.apply(parsed_cuke.uri, &parsed_cuke.cuke))
+        .map(|parsed_cuke| CukeInfo {
+            uri: parsed_cuke.uri.to_owned(),
+            name: parsed_cuke.cuke.name.to_string(),
+            line: parsed_cuke.cuke.locations[parsed_cuke.cuke.locations.len() - 1].line,
+            tags: parsed_cuke.cuke.tags.iter()
+                .map(|tag| tag.name.to_string())
+                .collect(),
+        })
+        .collect();
+
+    Ok(cukes)
+}
 
-            run_parallel_scenarios(runner, filters, &event_bus, &config);
+/// Matches the steps of the cukes that would be run with the config against the step definitions
+/// and reports the step definitions that match the same steps.
+pub(crate) fn check_glue(glue: &Glue, config: &Config<'_>) -> crate::error::Result<GlueCheck> {
+    let rerun_file_content = read_rerun_file(config)?;
+    let filters = Filters::new(config, rerun_file_content.as_deref())?;
+    let feature_files = FeatureFiles::new(config)?;
 
-            test_summary_listener.print_test_summary();
-            exit_status_listener.get_exit_status(config.strict)
+    let mut id_generator = IncrementingIdGenerator::new();
+    let mut source_errors = Vec::new();
+    let parsed_gherkin_documents = parse_gherking_documents(&feature_files, &mut id_generator, &mut source_errors);
+    fail_on_source_errors(source_errors)?;
+    let event_bus = EventBus::new(Vec::new());
+    let parsed_cukes = parse_cukes(&parsed_gherkin_documents, &event_bus, &mut id_generator);
+
+    let mut glue_check = GlueCheck { overlaps: Vec::new() };
+    // background steps are part of every cuke of a feature, so they are only matched once
+    let mut checked_steps = HashSet::new();
+
+    for parsed_cuke in &parsed_cukes {
+        if !filters.apply(parsed_cuke.uri, &parsed_cuke.cuke) {
+            continue;
+        }
+
+        let cuke = &parsed_cuke.cuke;
+        let steps = cuke.feature_background_steps.iter()
+            .chain(&cuke.rule_background_steps)
+            .chain(&cuke.scenario_steps);
+
+        for step in steps {
+            let step_line = step.locations.last()
+                .map(|location| location.line)
+                .unwrap_or(0);
+            let step_description = format!("{}:{} {}{}", parsed_cuke.uri, step_line, step.keyword, step.text);
+            if !checked_steps.insert(step_description.clone()) {
+                continue;
+            }
+
+            if let StepDefinitionMatch::Ambiguous(ambiguous_match) = glue.step_definition_match(parsed_cuke.uri, step) {
+                glue_check.add_ambiguous_step(ambiguous_match.get_candidates(), step_description);
+            }
         }
-    };
+    }
 
-    exit_status
+    Ok(glue_check)
+}
+
+fn read_rerun_file(config: &Config<'_>) -> crate::error::Result<Option<String>> {
+    match config.rerun_file {
+        Some(rerun_file) => {
+            let content = fs::read_to_string(rerun_file).map_err(|err| {
+                io::Error::new(err.kind(), format!("could not read rerun file \"{}\": {}", rerun_file.display(), err))
+            })?;
+            Ok(Some(content))
+        }
+        None => Ok(None),
+    }
 }
 
 struct ParsedGherkinDocument {
@@ -117,122 +225,261 @@ struct ParsedCuke<'d> {
     cuke: Cuke<'d>,
 }
 
-fn run_sequential(runner: Runner, filters: Filters<'_>, event_bus: &EventBus<'_>, config: &Config<'_>) {
+struct FeatureCukes<'d> {
+    uri: &'d str,
+    cukes: Vec<Cuke<'d>>,
+}
+
+fn run_sequential(mut runner: Runner, filters: Filters<'_>, event_bus: &EventBus<'_>, feature_files: &FeatureFiles<'_>) {
     let mut id_generator = IncrementingIdGenerator::new();
-    let parsed_gherkin_documents = parse_gherking_documents(config, &mut id_generator);
+    let mut source_errors = Vec::new();
+    let parsed_gherkin_documents = parse_gherking_documents(feature_files, &mut id_generator, &mut source_errors);
     let parsed_cukes = parse_cukes(&parsed_gherkin_documents, event_bus, &mut id_generator);
 
+    runner.send_step_definitions(event_bus);
     event_bus.send(Event::TestRunStarted {
         time: SystemTime::now(),
         num_cukes: parsed_cukes.len(),
     });
+    for source_error in &source_errors {
+        source_error.send(event_bus);
+    }
 
-    for parsed_cuke in parsed_cukes {
-        if filters.apply(parsed_cuke.uri, &parsed_cuke.cuke) {
-            runner.run(&parsed_cuke.uri, parsed_cuke.cuke, event_bus);
+    if runner.run_before_all_hooks(event_bus) {
+        for feature_cukes in group_cukes_by_feature(parsed_cukes, &filters) {
+            let feature_run = runner.create_feature_run(feature_cukes.uri, &feature_cukes.cukes);
+            for cuke in feature_cukes.cukes {
+                runner.run(&feature_run, cuke, event_bus);
+            }
         }
     }
 
+    runner.run_after_all_hooks(event_bus);
+
     event_bus.send(Event::TestRunFinished {
         time: SystemTime::now(),
     });
 }
 
-fn run_parallel_features(runner: Runner, filters: Filters<'_>, event_bus: &SyncEventBus<'_>, config: &Config<'_>) {
+fn run_parallel_features(
+    mut runner: Runner,
+    filters: Filters<'_>,
+    event_bus: &SyncEventBus<'_>,
+    feature_files: &FeatureFiles<'_>,
+    thread_pool: &ThreadPool,
+) {
     let mut id_generator = IncrementingIdGenerator::new();
-    let parsed_gherkin_documents = parse_gherking_documents(config, &mut id_generator);
+    let mut source_errors = Vec::new();
+    let parsed_gherkin_documents = parse_gherking_documents(feature_files, &mut id_generator, &mut source_errors);
     let parsed_cukes = parse_cukes(&parsed_gherkin_documents, event_bus, &mut id_generator);
 
+    runner.send_step_definitions(event_bus);
     event_bus.send(Event::TestRunStarted {
         time: SystemTime::now(),
         num_cukes: parsed_cukes.len(),
     });
-
-    let mut feature_cukes = HashMap::with_capacity(parsed_cukes.len());
-    for parsed_cuke in parsed_cukes {
-        feature_cukes.entry(parsed_cuke.uri)
-            .or_insert_with(Vec::new)
-            .push(parsed_cuke.cuke);
+    for source_error in &source_errors {
+        source_error.send(event_bus);
     }
-    feature_cukes.shrink_to_fit();
 
-    feature_cukes.into_par_iter().for_each(|(uri, cukes)| {
-        for cuke in cukes {
-            if filters.apply(uri, &cuke) {
-                runner.run(uri, cuke, event_bus);
+    if runner.run_before_all_hooks(event_bus) {
+        let features_cukes = group_cukes_by_feature(parsed_cukes, &filters);
+        let runner = &runner;
+
+        thread_pool.install(|| features_cukes.into_par_iter().for_each(|feature_cukes| {
+            let feature_run = runner.create_feature_run(feature_cukes.uri, &feature_cukes.cukes);
+            for cuke in feature_cukes.cukes {
+                runner.run(&feature_run, cuke, event_bus);
             }
-        }
-    });
+        }));
+    }
+
+    runner.run_after_all_hooks(event_bus);
 
     event_bus.send(Event::TestRunFinished {
         time: SystemTime::now(),
     });
 }
 
-fn run_parallel_scenarios(runner: Runner, filters: Filters<'_>, event_bus: &SyncEventBus<'_>, config: &Config<'_>) {
+fn run_parallel_scenarios(
+    mut runner: Runner,
+    filters: Filters<'_>,
+    event_bus: &SyncEventBus<'_>,
+    feature_files: &FeatureFiles<'_>,
+    thread_pool: &ThreadPool,
+) {
     let mut id_generator = IncrementingIdGenerator::new();
-    let parsed_gherkin_documents = parse_gherking_documents(config, &mut id_generator);
+    let mut source_errors = Vec::new();
+    let parsed_gherkin_documents = parse_gherking_documents(feature_files, &mut id_generator, &mut source_errors);
     let parsed_cukes = parse_cukes(&parsed_gherkin_documents, event_bus, &mut id_generator);
 
+    runner.send_step_definitions(event_bus);
     event_bus.send(Event::TestRunStarted {
         time: SystemTime::now(),
         num_cukes: parsed_cukes.len(),
     });
+    for source_error in &source_errors {
+        source_error.send(event_bus);
+    }
 
-    parsed_cukes.into_par_iter().for_each(|parsed_cuke| {
-        if filters.apply(parsed_cuke.uri, &parsed_cuke.cuke) {
-            runner.run(parsed_cuke.uri, parsed_cuke.cuke, event_bus);
-        }
-    });
+    if runner.run_before_all_hooks(event_bus) {
+        let features_cukes = group_cukes_by_feature(parsed_cukes, &filters);
+        let feature_runs = features_cukes.iter()
+            .map(|feature_cukes| runner.create_feature_run(feature_cukes.uri, &feature_cukes.cukes))
+            .collect::<Vec<FeatureRun<'_, '_>>>();
+
+        let cukes = features_cukes.into_iter()
+            .zip(&feature_runs)
+            .flat_map(|(feature_cukes, feature_run)| feature_cukes.cukes.into_iter()
+                .map(move |cuke| (feature_run, cuke)))
+            .collect::<Vec<(&FeatureRun<'_, '_>, Cuke<'_>)>>();
+        let runner = &runner;
+
+        thread_pool.install(|| cukes.into_par_iter().for_each(|(feature_run, cuke)| {
+            runner.run(feature_run, cuke, event_bus);
+        }));
+    }
+
+    runner.run_after_all_hooks(event_bus);
 
     event_bus.send(Event::TestRunFinished {
         time: SystemTime::now(),
     });
 }
 
-fn init_rayon() {
-    rayon::ThreadPoolBuilder::new()
+/// Groups the cukes that pass the filters by their feature file, keeping their order.
+fn group_cukes_by_feature<'d>(parsed_cukes: Vec<ParsedCuke<'d>>, filters: &Filters<'_>)
+    -> Vec<FeatureCukes<'d>>
+{
+    let mut features_cukes: Vec<FeatureCukes<'d>> = Vec::new();
+
+    for parsed_cuke in parsed_cukes {
+        if !filters.apply(parsed_cuke.uri, &parsed_cuke.cuke) {
+            continue;
+        }
+
+        match features_cukes.last_mut() {
+            Some(feature_cukes) if feature_cukes.uri == parsed_cuke.uri => {
+                feature_cukes.cukes.push(parsed_cuke.cuke);
+            }
+            _ => features_cukes.push(FeatureCukes {
+                uri: parsed_cuke.uri,
+                cukes: vec![parsed_cuke.cuke],
+            }),
+        }
+    }
+
+    features_cukes
+}
+
+/// Builds the thread pool of a parallel run.
+///
+/// The pool is local to the run instead of the global rayon pool,
+/// which can only be built once per process and may already be used by the glue code.
+fn build_thread_pool(threads: Option<usize>) -> crate::error::Result<ThreadPool> {
+    ThreadPoolBuilder::new()
+        .num_threads(threads.unwrap_or(0))
         .thread_name(|thread_index| format!("cuke-runner-{}", thread_index))
-        .build_global()
-        .expect("Failed to build global rayon thread pool");
+        .build()
+        .map_err(|err| Error::from(io::Error::new(io::ErrorKind::Other, err.to_string())))
 }
 
+/// Parses the feature files and adds the files that cannot be read or parsed to the source errors.
 fn parse_gherking_documents(
-    config: &Config<'_>,
+    feature_files: &FeatureFiles<'_>,
     id_generator: &mut dyn IdGenerator,
+    source_errors: &mut Vec<SourceError>,
 ) -> Vec<ParsedGherkinDocument>
 {
-    let walk_dir = WalkDir::new(config.features_dir)
-        .follow_links(true);
-
     let builder = gherkin::DocumentBuilder::with_id_generator(id_generator);
     let mut gherkin_parser = gherkin::Parser::with_builder(builder);
 
-    walk_dir.into_iter()
-        .map(Result::unwrap)
-        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".feature"))
-        .map(DirEntry::into_path)
-        .map(|path| {
-            let source = match fs::read_to_string(&path) {
-                Ok(source) => source,
-                Err(err) => panic!("could not read feature file \"{}\": {}", &path.display(), err),
-            };
-
-            let gherkin_document = match gherkin_parser.parse_str(&source) {
-                Ok(document) => document,
-                Err(err) => panic!("could not parse feature file \"{}\": {}", &path.display(), err),
-            };
-
-            let path_relative_to_features_dir = path.strip_prefix(config.features_dir).unwrap();
-            let uri = path_relative_to_features_dir.display().to_string();
+    let mut parsed_gherkin_documents = Vec::new();
 
-            ParsedGherkinDocument {
+    for FeatureFile { uri, source } in feature_files.find(source_errors) {
+        let source = match source {
+            FeatureSource::Path(path) => match fs::read_to_string(&path) {
+                Ok(source) => source,
+                Err(err) => {
+                    source_errors.push(SourceError::new(uri, None, Error::from(err)));
+                    continue;
+                }
+            },
+            FeatureSource::Embedded(source) => source.to_owned(),
+        };
+
+        match gherkin_parser.parse_str(&source) {
+            Ok(document) => parsed_gherkin_documents.push(ParsedGherkinDocument {
                 uri,
                 source,
-                document: gherkin_document,
+                document,
+            }),
+            Err(err) => {
+                let message = err.to_string();
+                source_errors.push(SourceError::new(uri, parse_error_location(&message), Error::from(err)));
             }
-        })
-        .collect::<Vec<ParsedGherkinDocument>>()
+        }
+    }
+
+    parsed_gherkin_documents
+}
+
+/// A feature file that could not be read or parsed.
+///
+/// Source errors are collected while the feature files are parsed,
+/// so that they can be sent as `Event::TestSourceParseError` after `Event::TestRunStarted`.
+#[derive(Debug)]
+pub(crate) struct SourceError {
+    uri: String,
+    location: Option<(u32, u32)>,
+    error: Error,
+}
+
+impl SourceError {
+    pub(crate) fn new(uri: String, location: Option<(u32, u32)>, error: Error) -> SourceError {
+        SourceError {
+            uri,
+            location,
+            error,
+        }
+    }
+
+    fn send(&self, event_publisher: &dyn EventPublisher) {
+        event_publisher.send(Event::TestSourceParseError {
+            time: SystemTime::now(),
+            uri: &self.uri,
+            line: self.location.map(|(line, _)| line),
+            column: self.location.map(|(_, column)| column),
+            error: &self.error,
+        });
+    }
+}
+
+/// Fails with the first source error,
+/// because the cukes of a config cannot be listed or checked without all of its feature files.
+fn fail_on_source_errors(source_errors: Vec<SourceError>) -> crate::error::Result<()> {
+    match source_errors.into_iter().next() {
+        Some(SourceError { uri, error, .. }) => Err(Error::InvalidFeatureFile {
+            uri,
+            message: error.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Extracts the location of gherkin parse errors like `(3:5): expected: #Feature, ...`.
+fn parse_error_location(message: &str) -> Option<(u32, u32)> {
+    let message = message.trim_start();
+    if !message.starts_with('(') {
+        return None;
+    }
+
+    let end = message.find(')')?;
+    let mut parts = message[1..end].split(':');
+    let line = parts.next()?.trim().parse::<u32>().ok()?;
+    let column = parts.next()?.trim().parse::<u32>().ok()?;
+
+    Some((line, column))
 }
 
 fn parse_cukes<'d>(
@@ -269,3 +516,168 @@ fn parse_cukes<'d>(
         })
         .collect::<Vec<ParsedCuke<'_>>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Duration;
+
+    use crate::api::{HookTestStep, HookType, TestResultStatus};
+    use crate::glue::{EmbeddedFeatureFile, StaticGlueDefinitions};
+    use crate::glue::error::ExecutionError;
+    use crate::glue::feature::Feature;
+    use crate::glue::global::Global;
+    use crate::glue::hook::{StaticFeatureHookDef, StaticGlobalHookDef};
+    use crate::glue::location::StaticGlueCodeLocation;
+    use crate::glue::scenario::Scenario;
+    use crate::glue::step::{ExpressionType, StaticStepDef, StepKeyword};
+    use crate::glue::step::argument::StepArgument;
+
+    use super::*;
+
+    #[test]
+    fn parses_gherkin_error_locations() {
+        assert_eq!(parse_error_location("(3:5): expected: #Feature, got 'Scenario:'"), Some((3, 5)));
+        assert_eq!(parse_error_location("inconsistent cell count"), None);
+        assert_eq!(parse_error_location("(unknown): expected"), None);
+    }
+
+    #[test]
+    fn fails_on_the_first_source_error() {
+        let source_errors = vec![
+            SourceError::new("a.feature".to_owned(), None, Error::from(io::Error::new(io::ErrorKind::NotFound, "not found"))),
+            SourceError::new("b.feature".to_owned(), None, Error::from(io::Error::new(io::ErrorKind::Other, "other"))),
+        ];
+
+        let err = fail_on_source_errors(source_errors).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid feature file \"a.feature\": not found");
+        assert!(fail_on_source_errors(Vec::new()).is_ok());
+    }
+
+    fn sleeping_step_fn(_scenario: &mut Scenario, _arguments: &[StepArgument<'_>])
+        -> ::std::result::Result<(), ExecutionError>
+    {
+        thread::sleep(Duration::from_secs(2));
+        Ok(())
+    }
+
+    fn feature_hook_fn(_feature: &mut Feature) -> ::std::result::Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    fn global_hook_fn(_global: &mut Global) -> ::std::result::Result<(), ExecutionError> {
+        Ok(())
+    }
+
+    static SLEEPING_STEPS: &[&StaticStepDef] = &[
+        &StaticStepDef {
+            name: "wait",
+            keyword: StepKeyword::When,
+            expression: "^I wait$",
+            expression_type: ExpressionType::Regex,
+            parameter_types: &[],
+            timeout: Some(Duration::from_millis(50)),
+            step_fn: sleeping_step_fn,
+            step_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/wait.rs", line: 1 },
+        },
+    ];
+
+    static AFTER_FEATURE_HOOKS: &[&StaticFeatureHookDef] = &[
+        &StaticFeatureHookDef {
+            name: "after_feature",
+            order: 0,
+            tag_expression: "",
+            hook_fn: feature_hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/wait.rs", line: 2 },
+        },
+    ];
+
+    static AFTER_ALL_HOOKS: &[&StaticGlobalHookDef] = &[
+        &StaticGlobalHookDef {
+            name: "after_all",
+            order: 0,
+            hook_fn: global_hook_fn,
+            hook_fn_location_fn: || StaticGlueCodeLocation { file: "tests/steps/wait.rs", line: 3 },
+        },
+    ];
+
+    static TIMEOUT_FEATURES: &[EmbeddedFeatureFile] = &[
+        EmbeddedFeatureFile {
+            uri: "timeout.feature",
+            source: "Feature: Timeout\n\n  Scenario: Slow step\n    When I wait\n",
+        },
+    ];
+
+    #[derive(Debug, Default)]
+    struct HookResultListener {
+        hook_results: Mutex<Vec<(HookType, TestResultStatus)>>,
+    }
+
+    impl EventListener for HookResultListener {
+        fn on_event(&self, event: &Event<'_, '_>) {
+            match *event {
+                Event::FeatureHookFinished { hook, result, .. } |
+                Event::GlobalHookFinished { hook, result, .. } => {
+                    self.hook_results.lock().unwrap().push((hook.get_hook_type(), result.status));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn fails_after_hooks_whose_data_is_used_by_a_timed_out_step() {
+        let static_glue_definitions = &[
+            StaticGlueDefinitions {
+                before_all_hooks: &[],
+                before_feature_hooks: &[],
+                before_scenario_hooks: &[],
+                before_step_hooks: &[],
+                steps: SLEEPING_STEPS,
+                parameter_types: &[],
+                after_step_hooks: &[],
+                after_scenario_hooks: &[],
+                after_feature_hooks: AFTER_FEATURE_HOOKS,
+                after_all_hooks: AFTER_ALL_HOOKS,
+            },
+        ];
+        let glue = Glue::from((PathBuf::from("/crate/tests"), &static_glue_definitions[..]));
+        let hook_result_listener = HookResultListener::default();
+        let output_dir = env::temp_dir().join("cuke_runner_timeout_test");
+
+        let run_outcome = run(glue, Config {
+            features_dir: Path::new("features"),
+            feature_paths: &[],
+            include: &[],
+            exclude: &[],
+            embedded_features: Some(TIMEOUT_FEATURES),
+            output_dir: &output_dir,
+            strict: false,
+            colored_output: false,
+            dry_run: false,
+            tags: &[],
+            tag_predicates: &[],
+            names: &[],
+            lines: &[],
+            rerun_file: None,
+            step_timeout: None,
+            retries: 0,
+            fail_on_flaky: false,
+            threads: None,
+            execution_mode: ExecutionMode::Sequential {
+                event_listeners: &[&hook_result_listener],
+            },
+        }).unwrap();
+
+        assert!(!run_outcome.success);
+        assert_eq!(run_outcome.failed_scenarios.len(), 1);
+        assert_eq!(*hook_result_listener.hook_results.lock().unwrap(), vec![
+            (HookType::AfterFeature, TestResultStatus::Failed),
+            (HookType::AfterAll, TestResultStatus::Failed),
+        ]);
+    }
+}
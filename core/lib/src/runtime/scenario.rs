@@ -1,4 +1,5 @@
-use std::time::SystemTime;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
 use gherkin::cuke::{Cuke, Tag};
 
@@ -7,6 +8,7 @@ use crate::runner::EventPublisher;
 use crate::api::{TestResult, TestResultStatus};
 use crate::api::event::Event;
 use crate::glue;
+use crate::glue::feature::Feature;
 
 #[derive(Debug)]
 pub struct Scenario<'a, 'b> {
@@ -17,11 +19,14 @@ pub struct Scenario<'a, 'b> {
     id: String,
     lines: Vec<u32>,
     event_publisher: &'b dyn EventPublisher,
+    start_instant: Instant,
     pub(crate) glue_scenario: glue::scenario::Scenario,
 }
 
 impl<'a, 'b> Scenario<'a, 'b> {
-    pub fn new(uri: &'a str, cuke: &'a Cuke<'_>, event_publisher: &'b dyn EventPublisher) -> Scenario<'a, 'b> {
+    pub fn new(uri: &'a str, cuke: &'a Cuke<'_>, feature_data: Arc<Feature>, event_publisher: &'b dyn EventPublisher)
+        -> Scenario<'a, 'b>
+    {
         let test_results = Vec::new();
         let tags = &cuke.tags;
         let name = &cuke.name;
@@ -39,7 +44,8 @@ impl<'a, 'b> Scenario<'a, 'b> {
             id,
             lines,
             event_publisher,
-            glue_scenario: glue::scenario::Scenario::new(),
+            start_instant: Instant::now(),
+            glue_scenario: glue::scenario::Scenario::new(feature_data),
         }
     }
 
@@ -95,4 +101,9 @@ impl<'a, 'b> Scenario<'a, 'b> {
     pub fn get_lines(&self) -> &[u32] {
         &self.lines
     }
+
+    /// The time since the scenario was started.
+    pub fn get_elapsed(&self) -> Duration {
+        self.start_instant.elapsed()
+    }
 }
@@ -0,0 +1,182 @@
+/// The argument that is attached to an undefined step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnippetArgument {
+    DocString,
+    DataTable,
+}
+
+/// Generates a step definition snippet that matches the given step text.
+///
+/// Integers, decimal numbers and quoted strings of the step text are turned into capture groups
+/// with `i32`, `f64` and `&str` parameters.
+pub fn generate_snippet(attribute: &str, text: &str, argument: Option<SnippetArgument>) -> String {
+    let (pattern, name_text, capture_types) = pattern(text);
+
+    let mut parameters = capture_types.iter()
+        .enumerate()
+        .map(|(index, capture_type)| format!("arg{}: {}", index + 1, capture_type))
+        .collect::<Vec<String>>();
+    match argument {
+        Some(SnippetArgument::DocString) => parameters.push("doc_string: &str".to_owned()),
+        Some(SnippetArgument::DataTable) => parameters.push("data_table: &DataTable<'_>".to_owned()),
+        None => {}
+    }
+
+    format!(
+        "#[{}(\"{}\")]\n\
+         pub fn {}({}) {{\n    \
+             // Write code here that turns the phrase above into concrete actions\n    \
+             unimplemented!()\n\
+         }}\n",
+        attribute,
+        escape_string_literal(&pattern),
+        function_name(&name_text),
+        parameters.join(", "),
+    )
+}
+
+/// Returns the name of the step attribute (`given`, `when`, `then` or `step`)
+/// for the last of the given step keywords.
+///
+/// Conjunctions like "And " or "But " use the attribute of the keyword before them.
+pub fn step_attribute<'k, I>(keywords: I) -> &'static str
+    where I: DoubleEndedIterator<Item = &'k str>
+{
+    for keyword in keywords.rev() {
+        match keyword.trim() {
+            "Given" => return "given",
+            "When" => return "when",
+            "Then" => return "then",
+            "And" | "But" | "*" => continue,
+            _ => return "step",
+        }
+    }
+
+    "step"
+}
+
+/// Returns the anchored regex of the step text, the text without the captured parts
+/// and the parameter types of the capture groups.
+fn pattern(text: &str) -> (String, String, Vec<&'static str>) {
+    let mut pattern = String::from("^");
+    let mut name_text = String::with_capacity(text.len());
+    let mut capture_types = Vec::new();
+    let mut previous_char = None;
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '"' {
+            if let Some(end) = rest[1..].find('"') {
+                pattern.push_str("\"([^\"]*)\"");
+                name_text.push(' ');
+                capture_types.push("&str");
+                previous_char = Some('"');
+                rest = &rest[end + 2..];
+                continue;
+            }
+        }
+
+        if c.is_ascii_digit() && !previous_char.map_or(false, is_word_char) {
+            let mut end = digits_end(rest);
+            let mut capture = ("(\\d+)", "i32");
+            if rest[end..].starts_with('.') && rest[end + 1..].starts_with(|c: char| c.is_ascii_digit()) {
+                end = end + 1 + digits_end(&rest[end + 1..]);
+                capture = ("(\\d+\\.\\d+)", "f64");
+            }
+            let next_char = rest[end..].chars().next();
+
+            if !next_char.map_or(false, is_word_char) {
+                pattern.push_str(capture.0);
+                name_text.push(' ');
+                capture_types.push(capture.1);
+                previous_char = Some(c);
+                rest = &rest[end..];
+                continue;
+            }
+        }
+
+        let (literal, remaining) = rest.split_at(c.len_utf8());
+        pattern.push_str(&regex::escape(literal));
+        name_text.push_str(literal);
+        previous_char = Some(c);
+        rest = remaining;
+    }
+
+    pattern.push('$');
+    (pattern, name_text, capture_types)
+}
+
+fn digits_end(text: &str) -> usize {
+    text.find(|c: char| !c.is_ascii_digit()).unwrap_or_else(|| text.len())
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn function_name(text: &str) -> String {
+    let name = text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<String>>()
+        .join("_");
+
+    match name.chars().next() {
+        None => "step".to_owned(),
+        Some(c) if c.is_ascii_digit() => format!("step_{}", name),
+        Some(_) => name,
+    }
+}
+
+fn escape_string_literal(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_snippet_with_captures() {
+        let snippet = generate_snippet("given", "I have 42 cukes in \"my belly\"", None);
+
+        assert_eq!(snippet, "#[given(\"^I have (\\\\d+) cukes in \\\"([^\\\"]*)\\\"$\")]\n\
+            pub fn i_have_cukes_in(arg1: i32, arg2: &str) {\n    \
+                // Write code here that turns the phrase above into concrete actions\n    \
+                unimplemented!()\n\
+            }\n");
+    }
+
+    #[test]
+    fn generates_snippet_with_step_argument() {
+        let snippet = generate_snippet("then", "the result is:", Some(SnippetArgument::DocString));
+        assert!(snippet.starts_with("#[then(\"^the result is:$\")]\npub fn the_result_is(doc_string: &str) {"));
+
+        let snippet = generate_snippet("step", "the entries", Some(SnippetArgument::DataTable));
+        assert!(snippet.contains("pub fn the_entries(data_table: &DataTable<'_>) {"));
+    }
+
+    #[test]
+    fn escapes_regex_characters() {
+        let (pattern, _, capture_types) = pattern("the 3rd item costs $5.50 (or less)?");
+
+        assert_eq!(pattern, "^the 3rd item costs \\$(\\d+\\.\\d+) \\(or less\\)\\?$");
+        assert_eq!(capture_types, vec!["f64"]);
+    }
+
+    #[test]
+    fn generates_valid_function_names() {
+        assert_eq!(function_name("I press +"), "i_press");
+        assert_eq!(function_name("3rd   try"), "step_3rd_try");
+        assert_eq!(function_name(" - "), "step");
+    }
+
+    #[test]
+    fn uses_previous_keyword_for_conjunctions() {
+        assert_eq!(step_attribute(vec!["Given ", "And "].into_iter()), "given");
+        assert_eq!(step_attribute(vec!["Given ", "When ", "But "].into_iter()), "when");
+        assert_eq!(step_attribute(vec!["Then "].into_iter()), "then");
+        assert_eq!(step_attribute(vec!["* "].into_iter()), "step");
+        assert_eq!(step_attribute(vec!["Given ", "Angenommen "].into_iter()), "step");
+    }
+}
@@ -1,22 +1,25 @@
-//use std::time::Duration;
-use std::any::TypeId;
 use std::fmt;
+use std::time::Duration;
 
 use gherkin::cuke;
 
-use crate::api::GlueCodeLocation;
-use crate::glue::step::{StaticStepDef, StepFn};
+use crate::api::{self, GlueCodeLocation};
+use crate::glue::step::{StaticStepDef, StepFn, StepKeyword};
+use crate::glue::step::parameter_type::StaticParameterTypeDef;
 use crate::glue::step::argument::{StepArgument, DocString, DataTable};
-use crate::runtime::Scenario;
+use crate::glue::scenario::Scenario;
 
+use super::hook_definition::relative_location;
 use super::step_expression::StepExpression;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 #[derive(Clone)]
 pub struct StepDefinition {
+    pub name: &'static str,
+    pub keyword: StepKeyword,
     pub expression: StepExpression,
-    pub parameter_infos: Vec<TypeId>,
-//    pub timeout: Duration,
+    pub parameter_types: &'static [&'static str],
+    pub timeout: Option<Duration>,
     pub step_fn: StepFn,
     pub location: GlueCodeLocation,
 }
@@ -24,43 +27,38 @@ pub struct StepDefinition {
 impl fmt::Debug for StepDefinition {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
         f.debug_struct("StepDefinition")
+            .field("name", &self.name)
+            .field("keyword", &self.keyword)
             .field("expression", &self.expression)
-            .field("parameter_infos", &self.parameter_infos)
-//            .field("timeout", &self.timeout)
+            .field("parameter_types", &self.parameter_types)
+            .field("timeout", &self.timeout)
             .field("step_fn", &"<step_fn>")
             .field("location", &self.location)
             .finish()
     }
 }
 
-impl From<(&Path, &&StaticStepDef)> for StepDefinition {
-    fn from((base_path, static_step_def): (&Path, &&StaticStepDef)) -> Self {
-        let location = (static_step_def.step_fn_location_fn)();
-        let file_path = PathBuf::from(location.file);
-        let mut relative_file_path = file_path.as_path();
-        for ancestor in file_path.ancestors() {
-            if base_path.ends_with(ancestor) {
-                match file_path.strip_prefix(ancestor) {
-                    Ok(path) => {
-                        relative_file_path = path;
-                        break;
-                    },
-                    Err(_strip_prefix_error) => {
-                        panic!("unable to strip base path \"{}\" from path \"{}\"",
-                               base_path.display(), file_path.display());
-                    }
-                }
-            }
-        }
+impl From<(&Path, &&StaticStepDef, &[&'static StaticParameterTypeDef])> for StepDefinition {
+    fn from((base_path, static_step_def, parameter_types): (&Path, &&StaticStepDef, &[&'static StaticParameterTypeDef]))
+        -> Self
+    {
+        let location = relative_location(base_path, &(static_step_def.step_fn_location_fn)());
+
+        let expression = match StepExpression::new(static_step_def.expression,
+            static_step_def.expression_type, parameter_types) {
+            Ok(expression) => expression,
+            Err(err) => panic!("invalid step expression \"{}\" of step definition {} ({}): {}",
+                static_step_def.expression, static_step_def.name, location, err),
+        };
 
         StepDefinition {
-            expression: StepExpression::from_regex(static_step_def.expression),
-            parameter_infos: Vec::new(),
+            name: static_step_def.name,
+            keyword: static_step_def.keyword,
+            expression,
+            parameter_types: static_step_def.parameter_types,
+            timeout: static_step_def.timeout,
             step_fn: static_step_def.step_fn,
-            location: GlueCodeLocation {
-                file_path: relative_file_path.to_owned(),
-                line_number: location.line,
-            },
+            location,
         }
     }
 }
@@ -72,12 +70,20 @@ impl StepDefinition {
     /// Returns an empty `Vec` if it matches with 0 arguments
     /// and bigger sizes if it matches several.
     pub fn matched_arguments<'s>(&'s self, step: &'s cuke::Step<'_>) -> Option<Vec<StepArgument<'s>>> {
-        let mut matched_arguments = match self.expression.matched_arguments(&step.text) {
+        self.matched_text_arguments(&step.text, step.argument.as_ref())
+    }
+
+    /// Returns the list of arguments for a step with the text and the optional
+    /// doc string or data table argument, like `matched_arguments`.
+    pub fn matched_text_arguments<'s>(&'s self, text: &'s str, argument: Option<&'s cuke::Argument<'_>>)
+        -> Option<Vec<StepArgument<'s>>>
+    {
+        let mut matched_arguments = match self.expression.matched_arguments(text) {
             Some(arguments) => arguments,
             None => return None,
         };
 
-        match &step.argument {
+        match argument {
             Some(argument) => {
                 matched_arguments.reserve_exact(1);
 
@@ -101,20 +107,62 @@ impl StepDefinition {
         &self.location
     }
 
+    /// The name of the step definition function.
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The keyword of the step definition attribute.
+    pub fn get_keyword(&self) -> StepKeyword {
+        self.keyword
+    }
+
     /// The number of declared parameters of this step definition.
     pub fn get_parameter_count(&self) -> u8 {
-        self.parameter_infos.len() as u8
+        self.parameter_types.len() as u8
+    }
+
+    /// The type names of the declared parameters of this step definition.
+    pub fn get_parameter_types(&self) -> &'static [&'static str] {
+        self.parameter_types
+    }
+
+    /// The maximum duration of the step, if the step definition declares one.
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
     }
 
     /// Invokes the step definition.
-    pub fn execute(&self, scenario: &mut Scenario<'_, '_>, args: &[StepArgument<'_>])
+    pub fn execute(&self, scenario: &mut Scenario, args: &[StepArgument<'_>])
         -> ::std::result::Result<(), crate::glue::error::ExecutionError>
     {
-        (self.step_fn)(&mut scenario.glue_scenario, args)
+        (self.step_fn)(scenario, args)
     }
 
     /// The step definition pattern for error reporting only.
-    pub fn get_pattern(&self) -> &String {
-        unimplemented!();
+    pub fn get_pattern(&self) -> &str {
+        &self.expression.expression
+    }
+}
+
+impl api::StepDefinition for StepDefinition {
+    fn get_pattern(&self) -> &str {
+        StepDefinition::get_pattern(self)
+    }
+
+    fn get_name(&self) -> &str {
+        self.name
+    }
+
+    fn get_keyword(&self) -> StepKeyword {
+        self.keyword
+    }
+
+    fn get_location(&self) -> &GlueCodeLocation {
+        &self.location
+    }
+
+    fn get_parameter_types(&self) -> &[&str] {
+        self.parameter_types
     }
 }
@@ -1,7 +1,12 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::Duration;
+
 use gherkin::cuke;
+use crate::glue;
 use crate::glue::step::argument::StepArgument;
 
-use crate::error::{Result, Error};
+use crate::error::{Result, Error, StepDefinitionCandidate};
 use crate::api::GlueCodeLocation;
 use crate::runtime::{HookDefinition, StepDefinition};
 use crate::runtime::Scenario;
@@ -51,7 +56,7 @@ impl<'s> StepDefinitionMatch<'s> {
         }
     }
 
-    pub fn get_pattern(&self) -> Option<&String> {
+    pub fn get_pattern(&self) -> Option<&str> {
         match self {
             StepDefinitionMatch::Hook(hook) => hook.get_pattern(),
             StepDefinitionMatch::Cuke(cuke_step) => cuke_step.get_pattern(),
@@ -60,6 +65,23 @@ impl<'s> StepDefinitionMatch<'s> {
         }
     }
 
+    /// The matched step definition, if the step is matched by exactly one step definition.
+    pub fn get_step_definition(&self) -> Option<&StepDefinition> {
+        match self {
+            StepDefinitionMatch::Cuke(cuke_step) => Some(&*cuke_step.step_definition),
+            _ => None,
+        }
+    }
+
+    /// The timeout declared by the matched step or hook definition.
+    pub fn get_timeout(&self) -> Option<Duration> {
+        match self {
+            StepDefinitionMatch::Hook(hook) => hook.hook_definition.get_timeout(),
+            StepDefinitionMatch::Cuke(cuke_step) => cuke_step.step_definition.get_timeout(),
+            StepDefinitionMatch::Ambiguous(_) | StepDefinitionMatch::Undefined(_) => None,
+        }
+    }
+
     pub fn get_arguments(&self) -> &[StepArgument<'_>] {
         match self {
             StepDefinitionMatch::Hook(hook) => hook.get_arguments(),
@@ -68,6 +90,74 @@ impl<'s> StepDefinitionMatch<'s> {
             StepDefinitionMatch::Undefined(cuke_step) => cuke_step.get_arguments(),
         }
     }
+
+    /// Returns the step or hook as job that can be run on another thread,
+    /// `None` for ambiguous and undefined steps that do not run any glue code.
+    pub fn to_step_job(&self) -> Option<StepJob> {
+        match self {
+            StepDefinitionMatch::Hook(hook) => Some(StepJob::Hook(hook.hook_definition.clone())),
+            StepDefinitionMatch::Cuke(cuke_step) => Some(StepJob::Cuke {
+                step_definition: Arc::clone(&cuke_step.step_definition),
+                text: cuke_step.step.text.to_string(),
+                argument: cuke_step.step.argument.as_ref().map(owned_argument),
+            }),
+            StepDefinitionMatch::Ambiguous(_) | StepDefinitionMatch::Undefined(_) => None,
+        }
+    }
+}
+
+/// A step or hook that owns everything it needs to run,
+/// so that it can be run on another thread and abandoned if it does not finish in time.
+#[derive(Debug)]
+pub enum StepJob {
+    Hook(HookDefinition),
+    Cuke {
+        step_definition: Arc<StepDefinition>,
+        text: String,
+        argument: Option<cuke::Argument<'static>>,
+    },
+}
+
+impl StepJob {
+    pub fn run(&self, scenario: &mut glue::scenario::Scenario) -> Result<()> {
+        match self {
+            StepJob::Hook(hook_definition) => hook_definition.execute(scenario),
+            StepJob::Cuke { step_definition, text, argument } => {
+                let arguments = step_definition.matched_text_arguments(text, argument.as_ref())
+                    .expect("step text was already matched by the step definition");
+                step_definition.execute(scenario, &arguments)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Copies the doc string or data table argument of a step out of the feature file.
+fn owned_argument(argument: &cuke::Argument<'_>) -> cuke::Argument<'static> {
+    let owned_location = |location: &cuke::Location| cuke::Location {
+        line: location.line,
+        column: location.column,
+    };
+
+    match argument {
+        cuke::Argument::String(string) => cuke::Argument::String(cuke::String {
+            location: owned_location(&string.location),
+            content: Cow::Owned(string.content.to_string()),
+            media_type: Cow::Owned(string.media_type.to_string()),
+        }),
+        cuke::Argument::Table(table) => cuke::Argument::Table(cuke::Table {
+            rows: table.rows.iter()
+                .map(|row| cuke::Row {
+                    cells: row.cells.iter()
+                        .map(|cell| cuke::Cell {
+                            location: owned_location(&cell.location),
+                            value: Cow::Owned(cell.value.to_string()),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }),
+    }
 }
 
 
@@ -83,7 +173,7 @@ impl<'s> HookDefinitionMatch<'s> {
     }
 
     pub fn run_step(&self, scenario: &mut Scenario<'_, '_>) -> Result<()> {
-        self.hook_definition.execute(scenario)
+        self.hook_definition.execute(&mut scenario.glue_scenario)
     }
 
     pub fn dry_run_step(&self, _scenario: &mut Scenario<'_, '_>) -> Result<()> {
@@ -94,7 +184,7 @@ impl<'s> HookDefinitionMatch<'s> {
         Some(&self.hook_definition.get_location())
     }
 
-    pub fn get_pattern(&self) -> Option<&String> {
+    pub fn get_pattern(&self) -> Option<&str> {
         None
     }
 
@@ -105,7 +195,7 @@ impl<'s> HookDefinitionMatch<'s> {
 
 #[derive(Debug)]
 pub struct CukeStepDefinitionMatch<'s> {
-    pub step_definition: StepDefinition,
+    pub step_definition: Arc<StepDefinition>,
     pub feature_path: String,
     pub step: &'s cuke::Step<'s>,
     pub arguments: Vec<StepArgument<'s>>,
@@ -117,7 +207,7 @@ impl<'s> CukeStepDefinitionMatch<'s> {
     }
 
     fn run_step(&self, scenario: &mut Scenario<'_, '_>) -> Result<()> {
-        self.step_definition.execute(scenario, &self.arguments)?;
+        self.step_definition.execute(&mut scenario.glue_scenario, &self.arguments)?;
         Ok(())
     }
 
@@ -129,7 +219,7 @@ impl<'s> CukeStepDefinitionMatch<'s> {
         Some(&self.step_definition.get_location())
     }
 
-    fn get_pattern(&self) -> Option<&String> {
+    fn get_pattern(&self) -> Option<&str> {
         Some(self.step_definition.get_pattern())
     }
 
@@ -143,6 +233,8 @@ pub struct AmbiguousCukeStepDefinitionMatch<'s> {
     pub feature_path: String,
     pub step: &'s cuke::Step<'s>,
     pub arguments: Vec<StepArgument<'s>>,
+    /// All step definitions that match the step.
+    pub candidates: Vec<CukeStepDefinitionMatch<'s>>,
 }
 
 impl<'s> AmbiguousCukeStepDefinitionMatch<'s> {
@@ -150,8 +242,21 @@ impl<'s> AmbiguousCukeStepDefinitionMatch<'s> {
         &self.step
     }
 
+    /// Returns the patterns and locations of all step definitions that match the step.
+    pub(crate) fn get_candidates(&self) -> Vec<StepDefinitionCandidate> {
+        self.candidates.iter()
+            .map(|candidate| StepDefinitionCandidate {
+                pattern: candidate.step_definition.get_pattern().to_owned(),
+                location: candidate.step_definition.get_location().clone(),
+            })
+            .collect()
+    }
+
     fn run_step(&self, _scenario: &mut Scenario<'_, '_>) -> Result<()> {
-        unimplemented!("AmbiguousPickleStepDefinitionMatch::run_step");
+        Err(Error::AmbiguousStepDefinitions {
+            step: format!("{}{}", self.step.keyword, self.step.text),
+            candidates: self.get_candidates(),
+        })
     }
 
     fn dry_run_step(&self, scenario: &mut Scenario<'_, '_>) -> Result<()> {
@@ -162,7 +267,7 @@ impl<'s> AmbiguousCukeStepDefinitionMatch<'s> {
         None
     }
 
-    fn get_pattern(&self) -> Option<&String> {
+    fn get_pattern(&self) -> Option<&str> {
         None
     }
 
@@ -194,7 +299,7 @@ impl<'s> UndefinedCukeStepDefinitionMatch<'s> {
         None
     }
 
-    fn get_pattern(&self) -> Option<&String> {
+    fn get_pattern(&self) -> Option<&str> {
         None
     }
 
@@ -1,29 +1,140 @@
 use regex::Regex;
 
+use crate::glue::step::ExpressionType;
 use crate::glue::step::argument::{StepArgument, Expression};
+use crate::glue::step::cucumber_expression::{CucumberExpression, ParameterType};
+use crate::glue::step::parameter_type::{self, StaticParameterTypeDef};
 
 #[derive(Debug, Clone)]
 pub struct StepExpression {
+    /// The expression as declared by the step definition.
+    pub expression: String,
     pub regex: Regex,
+    /// The parameter types of a cucumber expression, `None` for regexes.
+    pub parameter_types: Option<Vec<ParameterType>>,
+    /// The user-defined parameter types of the `(?P<name>)` placeholders of a regex
+    /// by capture group index.
+    pub placeholder_types: Vec<Option<ParameterType>>,
 }
 
 impl StepExpression {
-    pub fn from_regex(regex: &str) -> StepExpression {
-        StepExpression {
-            regex: Regex::new(regex).unwrap(),
+    /// Compiles the expression, user-defined parameter types that it references are resolved
+    /// with the given parameter types.
+    pub fn new(expression: &str, expression_type: ExpressionType,
+        parameter_types: &[&'static StaticParameterTypeDef]) -> Result<StepExpression, String>
+    {
+        match expression_type {
+            ExpressionType::Regex => StepExpression::from_regex(expression, parameter_types),
+            ExpressionType::CucumberExpression => {
+                StepExpression::from_cucumber_expression(expression, parameter_types)
+            }
         }
     }
 
+    pub fn from_regex(regex: &str, parameter_types: &[&'static StaticParameterTypeDef])
+        -> Result<StepExpression, String>
+    {
+        let (resolved_regex, resolved_placeholders) = parameter_type::resolve_regex_placeholders(
+            regex, parameter_types)?;
+        let compiled_regex = Regex::new(&resolved_regex).map_err(|err| err.to_string())?;
+        let placeholder_types = compiled_regex.capture_names()
+            .map(|name| name.and_then(|name| resolved_placeholders.iter()
+                .find(|(placeholder_name, _)| *placeholder_name == name)
+                .map(|(_, parameter_type)| *parameter_type)))
+            .collect();
+
+        Ok(StepExpression {
+            expression: regex.to_owned(),
+            regex: compiled_regex,
+            parameter_types: None,
+            placeholder_types,
+        })
+    }
+
+    pub fn from_cucumber_expression(expression: &str, parameter_types: &[&'static StaticParameterTypeDef])
+        -> Result<StepExpression, String>
+    {
+        // the syntax was already validated by the step attribute,
+        // but user-defined parameter types are only known now
+        let cucumber_expression = CucumberExpression::parse_with_parameter_types(expression, parameter_types)?;
+
+        Ok(StepExpression {
+            expression: expression.to_owned(),
+            regex: Regex::new(cucumber_expression.regex()).map_err(|err| err.to_string())?,
+            parameter_types: Some(cucumber_expression.parameter_types().to_vec()),
+            placeholder_types: Vec::new(),
+        })
+    }
+
     pub fn matched_arguments<'s>(&'s self, text: &'s str) -> Option<Vec<StepArgument<'s>>> {
         let caps = self.regex.captures(text)?;
 
         let matched_arguments = caps.iter()
+            .enumerate()
             .skip(1) // The first match always corresponds to the overall match of the regex.
-            .filter_map(|opt_mat| {
-                opt_mat.map(|mat| StepArgument::Expression(Expression::from(mat)))
+            .filter_map(|(group_index, opt_mat)| opt_mat.map(|mat| (group_index, mat)))
+            .enumerate()
+            .map(|(argument_index, (group_index, mat))| {
+                // every parameter of a cucumber expression has exactly one participating group
+                let parameter_type = match self.parameter_types {
+                    Some(ref parameter_types) => parameter_types.get(argument_index).copied(),
+                    None => self.placeholder_types.get(group_index).copied().flatten(),
+                };
+
+                StepArgument::Expression(Expression::with_parameter_type(
+                    mat, parameter_type.map(ParameterType::name)))
             })
             .collect::<Vec<StepArgument<'_>>>();
 
         Some(matched_arguments)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static COLOR: StaticParameterTypeDef = StaticParameterTypeDef {
+        name: "color",
+        regex: "red|green|blue",
+        type_name: "Color",
+    };
+
+    fn parameter_types(expression: &StepExpression, text: &str) -> Vec<(String, Option<&'static str>)> {
+        expression.matched_arguments(text).unwrap().iter()
+            .map(|argument| match argument {
+                StepArgument::Expression(expression) => {
+                    (expression.value().to_owned(), expression.parameter_type())
+                }
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn resolves_user_defined_parameter_types() {
+        let expression = StepExpression::new("I paint {int} walls {color}",
+            ExpressionType::CucumberExpression, &[&COLOR]).unwrap();
+        assert_eq!(parameter_types(&expression, "I paint 3 walls red"), vec![
+            ("3".to_owned(), Some("int")),
+            ("red".to_owned(), Some("color")),
+        ]);
+        assert!(expression.matched_arguments("I paint 3 walls purple").is_none());
+
+        let expression = StepExpression::new("^I paint (\\d+) walls (?P<color>)$",
+            ExpressionType::Regex, &[&COLOR]).unwrap();
+        assert_eq!(parameter_types(&expression, "I paint 3 walls blue"), vec![
+            ("3".to_owned(), None),
+            ("blue".to_owned(), Some("color")),
+        ]);
+    }
+
+    #[test]
+    fn rejects_undefined_parameter_types() {
+        let result = StepExpression::new("I paint it {colour}", ExpressionType::CucumberExpression, &[&COLOR]);
+        assert_eq!(result.unwrap_err(), "undefined parameter type {colour}");
+
+        let result = StepExpression::new("^I paint it (?P<colour>)$", ExpressionType::Regex, &[&COLOR]);
+        assert_eq!(result.unwrap_err(), "undefined parameter type (?P<colour>)");
+    }
+}
@@ -1,9 +1,12 @@
-use std::time::SystemTime;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 use gherkin::cuke::{Cuke, Tag};
 
 use crate::api::{self, TestResult, TestResultStatus};
 use crate::api::event::Event;
+use crate::error::Error;
+use crate::glue::feature::Feature;
 use crate::runner::{EventPublisher, CukeStepTestStep, HookTestStep};
 use crate::runtime;
 
@@ -14,7 +17,38 @@ pub struct TestCase<'c> {
     pub test_steps: Vec<CukeStepTestStep<'c>>,
     pub before_hooks: Vec<HookTestStep<'c>>,
     pub after_hooks: Vec<HookTestStep<'c>>,
-    pub dry_run: bool,
+    pub feature_data: Arc<Feature>,
+    /// Skips all hooks and steps, either on a dry run
+    /// or because a `BeforeFeature` hook did not pass.
+    pub skip: bool,
+    /// The message of an invalid tag like `@timeout(...)` or `@retry(...)` that fails the test case
+    /// without running its hooks and steps.
+    pub invalid_tag: Option<String>,
+    /// The timeout of steps and hooks that do not declare their own timeout.
+    pub step_timeout: Option<Duration>,
+    /// The timeout of the whole scenario from its `@timeout(...)` tag.
+    pub scenario_timeout: Option<Duration>,
+    /// How often the test case is retried if it failed.
+    pub retries: u32,
+}
+
+impl<'c> TestCase<'c> {
+    /// Returns the timeout of a step or hook of this test case.
+    ///
+    /// The timeout of the step or hook definition takes precedence over the configured
+    /// step timeout, but it never exceeds the time that is left of the scenario timeout.
+    pub fn get_step_timeout(&self, definition_timeout: Option<Duration>, scenario_elapsed: Duration)
+        -> Option<Duration>
+    {
+        let step_timeout = definition_timeout.or(self.step_timeout);
+        let remaining_scenario_timeout = self.scenario_timeout
+            .map(|timeout| timeout.checked_sub(scenario_elapsed).unwrap_or_default());
+
+        match (step_timeout, remaining_scenario_timeout) {
+            (Some(step_timeout), Some(remaining)) => Some(step_timeout.min(remaining)),
+            (step_timeout, remaining) => step_timeout.or(remaining),
+        }
+    }
 }
 
 impl<'s> api::TestCase for TestCase<'s> {
@@ -60,50 +94,80 @@ impl<'s> api::TestCase for TestCase<'s> {
         self.cuke.locations[0].line
     }
 
+    fn get_example_line(&self) -> u32 {
+        self.cuke.locations[self.cuke.locations.len() - 1].line
+    }
+
     fn get_tags(&self) -> &[Tag<'_>] {
         &self.cuke.tags
     }
 }
 
+/// Runs the test case and retries it with fresh scenario data as long as it fails
+/// and retries are left.
 pub fn run<EP: EventPublisher>(test_case: TestCase<'_>, event_publisher: &EP) {
+    let mut attempt = 0;
+    while run_attempt(&test_case, attempt, event_publisher) {
+        attempt += 1;
+    }
+}
+
+/// Runs a single attempt of the test case and returns whether it will be retried.
+fn run_attempt<EP: EventPublisher>(test_case: &TestCase<'_>, attempt: u32, event_publisher: &EP) -> bool {
     let start_time = SystemTime::now();
     event_publisher.send(Event::TestCaseStarted {
         time: start_time,
+        attempt,
         uri: test_case.uri,
         feature: test_case.cuke.feature,
         feature_background: test_case.cuke.feature_background,
         rule_background: test_case.cuke.rule_background,
         scenario: &test_case.cuke.scenario,
-        test_case: &test_case as &dyn api::TestCase,
+        test_case: test_case as &dyn api::TestCase,
     });
 
-    let mut skip_next_step = test_case.dry_run;
-    let mut scenario = runtime::Scenario::new(test_case.uri, &test_case.cuke, event_publisher);
+    let skip = test_case.skip || test_case.invalid_tag.is_some();
+    let mut skip_next_step = skip;
+    let mut scenario = runtime::Scenario::new(test_case.uri, &test_case.cuke,
+        Arc::clone(&test_case.feature_data), event_publisher);
 
     for before_hook in &test_case.before_hooks {
-        let hook_result = before_hook.run(event_publisher, &test_case, &mut scenario, test_case.dry_run);
+        let hook_result = before_hook.run(event_publisher, test_case, &mut scenario, skip);
         skip_next_step = skip_next_step || !hook_result.status.eq(&TestResultStatus::Passed);
         scenario.add_test_result(hook_result);
     }
 
     for step in &test_case.test_steps {
-        let step_result = step.run(event_publisher, &test_case, &mut scenario, skip_next_step);
+        let step_result = step.run(event_publisher, test_case, &mut scenario, skip_next_step);
         skip_next_step = skip_next_step || !step_result.status.eq(&TestResultStatus::Passed);
         scenario.add_test_result(step_result);
     }
 
     for after_hook in &test_case.after_hooks {
-        let hook_result = after_hook.run(event_publisher, &test_case, &mut scenario, test_case.dry_run);
+        let hook_result = after_hook.run(event_publisher, test_case, &mut scenario, skip);
         scenario.add_test_result(hook_result);
     }
 
+    if let Some(ref invalid_tag) = test_case.invalid_tag {
+        scenario.add_test_result(TestResult {
+            status: TestResultStatus::Failed,
+            duration: None,
+            error: Some(Error::InvalidTag(invalid_tag.clone())),
+        });
+    }
+
     let stop_time = SystemTime::now();
     let duration = match stop_time.duration_since(start_time) {
         Ok(duration) => duration,
         Err(system_time_error) => system_time_error.duration(),
     };
+    let status = match scenario.get_status() {
+        TestResultStatus::Passed if attempt > 0 => TestResultStatus::Flaky,
+        status => status,
+    };
+    let will_be_retried = status == TestResultStatus::Failed && attempt < test_case.retries;
     let test_result = TestResult {
-        status: scenario.get_status(),
+        status,
         duration: Some(duration),
         error: scenario.into_error(),
     };
@@ -115,6 +179,10 @@ pub fn run<EP: EventPublisher>(test_case: TestCase<'_>, event_publisher: &EP) {
         rule_background: test_case.cuke.rule_background,
         scenario: &test_case.cuke.scenario,
         result: &test_result,
-        test_case: &test_case as &dyn api::TestCase,
+        test_case: test_case as &dyn api::TestCase,
+        attempt,
+        will_be_retried,
     });
+
+    will_be_retried
 }
@@ -17,6 +17,10 @@ pub struct Feature {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub elements: Vec<Element>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub before: Vec<Hook>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub after: Vec<Hook>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<Tag>,
 }
 
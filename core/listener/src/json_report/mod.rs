@@ -6,7 +6,7 @@ use std::sync::Mutex;
 
 use gherkin::ast::{Background, Scenario};
 
-use cuke_runner::api::{HookType, TestCase, TestResult, TestStep};
+use cuke_runner::api::{HookTestStep, HookType, TestCase, TestResult, TestStep};
 use cuke_runner::api::event::{Event, EventListener};
 use cuke_runner::gherkin;
 use data::*;
@@ -56,6 +56,8 @@ pub struct JsonReportListener<W: Write + Send + Debug> {
 #[derive(Debug, Default)]
 struct Report<W: Write + Send + Debug> {
     features: HashMap<String, Feature>,
+    /// The number of elements of a feature before the current test case was started.
+    test_case_element_starts: HashMap<String, usize>,
     id_count: usize,
     writer: W,
 }
@@ -65,6 +67,7 @@ impl<W: Write + Send + Debug> JsonReportListener<W> {
         JsonReportListener {
             report: Mutex::new(RefCell::new(Report {
                 features: HashMap::new(),
+                test_case_element_starts: HashMap::new(),
                 id_count: 0,
                 writer,
             })),
@@ -80,6 +83,11 @@ impl<W: Write + Send + Debug> EventListener for JsonReportListener<W> {
                 let mut report = report_lock.borrow_mut();
                 report.add_feature(uri, feature);
             }
+            Event::TestCaseStarted { uri, attempt, .. } => {
+                let report_lock = self.report.lock().unwrap();
+                let mut report = report_lock.borrow_mut();
+                report.start_test_case_attempt(uri, attempt);
+            }
             Event::TestStepFinished {
                 uri,
                 feature_background,
@@ -95,6 +103,16 @@ impl<W: Write + Send + Debug> EventListener for JsonReportListener<W> {
                 report.add_test_step_result(uri, feature_background, rule_background, scenario,
                     test_case, test_step, result);
             }
+            Event::FeatureHookFinished {
+                uri,
+                hook,
+                result,
+                ..
+            } => {
+                let report_lock = self.report.lock().unwrap();
+                let mut report = report_lock.borrow_mut();
+                report.add_feature_hook_result(uri, hook, result);
+            }
             Event::TestRunFinished { .. } => {
                 let report_lock = self.report.lock().unwrap();
                 let mut report = report_lock.borrow_mut();
@@ -118,10 +136,37 @@ impl<W: Write + Send + Debug> Report<W> {
             line: feature.location.unwrap().line,
             description: feature.description.to_owned(),
             elements: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
             tags: feature.tags.iter().map(Tag::from).collect(),
         });
     }
 
+    /// Only reports the last attempt of a test case,
+    /// so a retry replaces the elements of the previous attempt.
+    fn start_test_case_attempt(&mut self, uri: &str, attempt: u32) {
+        let feature = self.features.get_mut(uri).unwrap();
+
+        if attempt == 0 {
+            self.test_case_element_starts.insert(uri.to_string(), feature.elements.len());
+        } else if let Some(&element_start) = self.test_case_element_starts.get(uri) {
+            feature.elements.truncate(element_start);
+        }
+    }
+
+    fn add_feature_hook_result(&mut self, uri: &str, hook_test_step: &dyn HookTestStep<'_>,
+        result: &TestResult)
+    {
+        let feature = self.features.get_mut(uri).unwrap();
+        let hook = Hook::from((hook_test_step, result));
+
+        match hook_test_step.get_hook_type() {
+            HookType::BeforeFeature => feature.before.push(hook),
+            HookType::AfterFeature => feature.after.push(hook),
+            _ => unreachable!("only feature hooks are part of a feature"),
+        }
+    }
+
     fn add_test_step_result(&mut self, uri: &str,
         feature_background: Option<&Background>, _rule_background: Option<&Background>,
         scenario: &Scenario, test_case: &dyn TestCase, test_step: &TestStep<'_, '_>,
@@ -137,6 +182,10 @@ impl<W: Write + Send + Debug> Report<W> {
                     HookType::AfterScenario => "after",
                     HookType::BeforeStep => unimplemented!(),
                     HookType::AfterStep => unimplemented!(),
+                    HookType::BeforeAll | HookType::AfterAll |
+                    HookType::BeforeFeature | HookType::AfterFeature => {
+                        unreachable!("global and feature hooks are not part of a test case")
+                    }
                 };
 
                 let element = if let Some(last_element) = feature.elements.last_mut() {
@@ -164,6 +213,10 @@ impl<W: Write + Send + Debug> Report<W> {
                     },
                     HookType::BeforeStep => unimplemented!(),
                     HookType::AfterStep => unimplemented!(),
+                    HookType::BeforeAll | HookType::AfterAll |
+                    HookType::BeforeFeature | HookType::AfterFeature => {
+                        unreachable!("global and feature hooks are not part of a test case")
+                    }
                 };
             }
             TestStep::Cuke(cuke_step_test_step) => {
@@ -22,3 +22,8 @@ pub use progress_bar::{ProgressBarListener, ProgressStyle};
 mod json_report;
 #[cfg(feature = "json_report")]
 pub use json_report::JsonReportListener;
+
+#[cfg(feature = "usage_report")]
+mod usage_report;
+#[cfg(feature = "usage_report")]
+pub use usage_report::UsageReportListener;
@@ -4,7 +4,8 @@ use std::ops::Deref;
 
 use unicode_segmentation::UnicodeSegmentation;
 
-use cuke_runner::api::{CukeStepTestStep, GlueCodeLocation, TestCase, TestResult, TestStep};
+use cuke_runner::Error;
+use cuke_runner::api::{CukeStepTestStep, GlueCodeLocation, HookTestStep, TestCase, TestResult, TestResultStatus, TestStep};
 use cuke_runner::api::event::{Event, EventListener};
 use cuke_runner::gherkin::ast::{Argument, Background, Examples, Feature, Scenario, Tag};
 use cuke_runner::gherkin::cuke;
@@ -47,6 +48,7 @@ struct Inner {
     current_scenario_outline: Option<u32>,
     current_examples: Option<u32>,
     location_indentation: usize,
+    snippets: Vec<String>,
 }
 
 impl Default for Inner {
@@ -58,6 +60,7 @@ impl Default for Inner {
             current_scenario_outline: None,
             current_examples: None,
             location_indentation: 0,
+            snippets: Vec::new(),
         }
     }
 }
@@ -102,12 +105,36 @@ impl EventListener for PrettyPrintListener {
                 ..
             } => self.inner.borrow_mut()
                 .handle_test_step_finished(test_step, result),
+            Event::FeatureHookFinished {
+                hook,
+                result,
+                ..
+            } |
+            Event::GlobalHookFinished {
+                hook,
+                result,
+                ..
+            } => self.inner.borrow()
+                .handle_hook_finished(hook, result),
             Event::Write {
                 text,
                 ..
             } => self.inner.borrow()
                 .handle_write(text),
-            Event::TestRunFinished { .. } => println!(),
+            Event::TestSourceParseError {
+                uri,
+                line,
+                error,
+                ..
+            } => self.inner.borrow()
+                .handle_test_source_parse_error(uri, line, error),
+            Event::SnippetSuggested {
+                snippet,
+                ..
+            } => self.inner.borrow_mut()
+                .handle_snippet_suggested(snippet),
+            Event::TestRunFinished { .. } => self.inner.borrow()
+                .handle_test_run_finished(),
             _ => {},
         }
     }
@@ -166,10 +193,51 @@ impl Inner {
         self.print_error(result);
     }
 
+    fn handle_hook_finished(&self, hook: &dyn HookTestStep<'_>, result: &TestResult) {
+        if result.error.is_some() {
+            let location = self.format_glue_code_location(hook.get_glue_code_location());
+            println!("\x1B[{}m{:?} hook\x1B[0m {}", result.status.ansi_color_code(),
+                hook.get_hook_type(), location);
+            self.print_error(result);
+        }
+    }
+
     fn handle_write(&self, text: &str) {
         println!("{}", text);
     }
 
+    fn handle_test_source_parse_error(&self, uri: &str, line: Option<u32>, error: &Error) {
+        let location = match line {
+            Some(line) => format!("{}:{}", uri, line),
+            None => uri.to_owned(),
+        };
+        println!("\x1B[{}mCould not parse feature file\x1B[0m \x1B[90m# {}\x1B[0m",
+            TestResultStatus::Failed.ansi_color_code(), location);
+        println!("\x1B[{}m{}\x1B[0m", TestResultStatus::Failed.ansi_color_code(), error);
+    }
+
+    fn handle_snippet_suggested(&mut self, snippet: &str) {
+        if !self.snippets.iter().any(|existing| existing == snippet) {
+            self.snippets.push(snippet.to_owned());
+        }
+    }
+
+    fn handle_test_run_finished(&self) {
+        println!();
+
+        if self.snippets.is_empty() {
+            return;
+        }
+
+        println!("\x1B[{}mYou can implement the undefined steps with these snippets:\x1B[0m",
+            TestResultStatus::Undefined.ansi_color_code());
+        for snippet in &self.snippets {
+            println!();
+            print!("\x1B[{}m{}\x1B[0m", TestResultStatus::Undefined.ansi_color_code(), snippet);
+        }
+        println!();
+    }
+
     fn print_step(&self, test_step: &dyn CukeStepTestStep<'_>, result: &TestResult) {
         let keyword = test_step.get_step_keyword();
         let step_text = test_step.get_step_text();
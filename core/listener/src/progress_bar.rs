@@ -58,7 +58,7 @@ impl EventListener for ProgressBarListener {
                 self.progress_bar.enable_steady_tick(100);
                 self.progress_bar.set_draw_target(ProgressDrawTarget::stderr())
             },
-            Event::TestCaseFinished { .. } => {
+            Event::TestCaseFinished { will_be_retried: false, .. } => {
                 self.progress_bar.inc(1);
             },
             Event::TestRunFinished { .. } => {
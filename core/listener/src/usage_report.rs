@@ -0,0 +1,289 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use cuke_runner::api::{StepDefinition, TestCase, TestResult, TestStep};
+use cuke_runner::api::event::{Event, EventListener};
+
+/// Reports which steps matched each step definition and how long they took.
+///
+/// Step definitions that never matched a step are listed as unused.
+/// At the end of the test run the usage is written as json to the writer
+/// and printed as a table to the console (stdout).
+///
+/// The `usage_report` feature needs to be enabled to use this event listener.
+///
+/// This listener implements `Sync` and thus can be used in parallel execution modes.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use std::path::PathBuf;
+/// use cuke_runner_listener::UsageReportListener;
+///
+/// let output_dir = &[
+///     env!("CARGO_MANIFEST_DIR"),
+///     "target",
+///     "cucumber",
+/// ].iter().collect::<PathBuf>();
+///
+/// std::fs::create_dir_all(&output_dir).unwrap();
+/// let usage_report_path = output_dir.join("usage.json");
+/// let mut usage_report_file = std::fs::File::create(usage_report_path).unwrap();
+///
+/// let event_listeners = &[
+///     &UsageReportListener::with_writer(&mut usage_report_file),
+/// ];
+/// ```
+#[derive(Debug)]
+pub struct UsageReportListener<W: Write + Send + Debug> {
+    report: Mutex<RefCell<Report<W>>>,
+}
+
+#[derive(Debug)]
+struct Report<W: Write + Send + Debug> {
+    step_definitions: Vec<StepDefinitionUsage>,
+    /// The steps of the running test cases by their uri and line,
+    /// which are only added to the usage if their attempt is not retried.
+    running_test_cases: HashMap<(String, u32), Vec<(usize, StepUsage)>>,
+    writer: W,
+}
+
+#[derive(Debug, Serialize)]
+struct UsageReport<'a> {
+    step_definitions: &'a [StepDefinitionUsage],
+    unused_step_definitions: Vec<&'a StepDefinitionUsage>,
+}
+
+#[derive(Debug, Serialize)]
+struct StepDefinitionUsage {
+    pattern: String,
+    name: String,
+    location: String,
+    count: usize,
+    /// The number of steps with a duration, which are part of the mean duration.
+    #[serde(skip)]
+    timed_count: u64,
+    /// The durations are in nanoseconds.
+    total_duration: u64,
+    mean_duration: u64,
+    max_duration: u64,
+    steps: Vec<StepUsage>,
+}
+
+#[derive(Debug, Serialize)]
+struct StepUsage {
+    text: String,
+    location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<u64>,
+}
+
+impl<W: Write + Send + Debug> UsageReportListener<W> {
+    pub fn with_writer(writer: W) -> UsageReportListener<W> {
+        UsageReportListener {
+            report: Mutex::new(RefCell::new(Report {
+                step_definitions: Vec::new(),
+                running_test_cases: HashMap::new(),
+                writer,
+            })),
+        }
+    }
+}
+
+impl<W: Write + Send + Debug> EventListener for UsageReportListener<W> {
+    fn on_event(&self, event: &Event<'_, '_>) {
+        match *event {
+            Event::StepDefinitionsRead { step_definitions, .. } => {
+                let report_lock = self.report.lock().unwrap();
+                let mut report = report_lock.borrow_mut();
+                report.add_step_definitions(step_definitions);
+            }
+            Event::TestStepFinished { test_case, test_step, result, .. } => {
+                let report_lock = self.report.lock().unwrap();
+                let mut report = report_lock.borrow_mut();
+                report.add_test_step_result(test_case, test_step, result);
+            }
+            Event::TestCaseFinished { test_case, will_be_retried, .. } => {
+                let report_lock = self.report.lock().unwrap();
+                let mut report = report_lock.borrow_mut();
+                report.finish_test_case(test_case, will_be_retried);
+            }
+            Event::TestRunFinished { .. } => {
+                let report_lock = self.report.lock().unwrap();
+                let mut report = report_lock.borrow_mut();
+                report.write();
+                report.print_table();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<W: Write + Send + Debug> Report<W> {
+    fn add_step_definitions(&mut self, step_definitions: &[&dyn StepDefinition]) {
+        self.step_definitions = step_definitions.iter()
+            .map(|step_definition| StepDefinitionUsage {
+                pattern: step_definition.get_pattern().to_owned(),
+                name: step_definition.get_name().to_owned(),
+                location: step_definition.get_location().to_string(),
+                count: 0,
+                timed_count: 0,
+                total_duration: 0,
+                mean_duration: 0,
+                max_duration: 0,
+                steps: Vec::new(),
+            })
+            .collect();
+    }
+
+    fn add_test_step_result(&mut self, test_case: &dyn TestCase, test_step: &TestStep<'_, '_>, result: &TestResult) {
+        let cuke_step = match test_step {
+            TestStep::Cuke(cuke_step) => cuke_step,
+            TestStep::Hook(_) => return,
+        };
+
+        // ambiguous and undefined steps have no pattern
+        let pattern = match cuke_step.get_pattern() {
+            Some(pattern) => pattern,
+            None => return,
+        };
+
+        let usage_index = match self.step_definitions.iter().position(|usage| usage.pattern == pattern) {
+            Some(usage_index) => usage_index,
+            None => return,
+        };
+
+        self.running_test_cases.entry(test_case_key(test_case))
+            .or_insert_with(Vec::new)
+            .push((usage_index, StepUsage {
+                text: format!("{}{}", cuke_step.get_step_keyword(), cuke_step.get_step_text()),
+                location: cuke_step.get_step_location(),
+                duration: result.duration.map(nanos),
+            }));
+    }
+
+    /// Adds the steps of the test case to the usage, unless the test case is run again.
+    fn finish_test_case(&mut self, test_case: &dyn TestCase, will_be_retried: bool) {
+        let steps = self.running_test_cases.remove(&test_case_key(test_case))
+            .unwrap_or_default();
+        if will_be_retried {
+            return;
+        }
+
+        for (usage_index, step) in steps {
+            self.step_definitions[usage_index].add_step(step);
+        }
+    }
+
+    fn write(&mut self) {
+        let report = UsageReport {
+            step_definitions: &self.step_definitions,
+            unused_step_definitions: self.step_definitions.iter()
+                .filter(|usage| usage.count == 0)
+                .collect(),
+        };
+        serde_json::to_writer(&mut self.writer, &report).unwrap();
+    }
+
+    fn print_table(&self) {
+        println!("Step definition usage:");
+        println!("{:>7}  {:>12}  {:>12}  {:>12}  Step definition", "Count", "Total", "Mean", "Max");
+        for usage in &self.step_definitions {
+            println!("{:>7}  {:>12}  {:>12}  {:>12}  {} \x1B[90m# {}\x1B[0m",
+                usage.count,
+                format_nanos(usage.total_duration),
+                format_nanos(usage.mean_duration),
+                format_nanos(usage.max_duration),
+                usage.pattern,
+                usage.location);
+        }
+
+        let unused = self.step_definitions.iter()
+            .filter(|usage| usage.count == 0)
+            .collect::<Vec<&StepDefinitionUsage>>();
+        if !unused.is_empty() {
+            println!();
+            println!("Unused step definitions: {}", unused.len());
+            for usage in unused {
+                println!("  {} \x1B[90m# {}\x1B[0m", usage.pattern, usage.location);
+            }
+        }
+        println!();
+    }
+}
+
+impl StepDefinitionUsage {
+    fn add_step(&mut self, step: StepUsage) {
+        self.count += 1;
+
+        // skipped steps have no duration and are not part of the mean duration
+        if let Some(duration) = step.duration {
+            self.timed_count += 1;
+            self.total_duration += duration;
+            self.max_duration = self.max_duration.max(duration);
+            self.mean_duration = self.total_duration / self.timed_count;
+        }
+
+        self.steps.push(step);
+    }
+}
+
+fn test_case_key(test_case: &dyn TestCase) -> (String, u32) {
+    (test_case.get_uri().to_owned(), test_case.get_example_line())
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos() as u64
+}
+
+fn format_nanos(nanos: u64) -> String {
+    format!("{:?}", Duration::from_nanos(nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    #[test]
+    fn test_send_sync() {
+        assert_send::<UsageReportListener<Vec<u8>>>();
+        assert_sync::<UsageReportListener<Vec<u8>>>();
+    }
+
+    #[test]
+    fn aggregates_step_durations() {
+        let mut usage = StepDefinitionUsage {
+            pattern: "^I add (\\d+)$".to_owned(),
+            name: "add".to_owned(),
+            location: "steps/calc.rs:12".to_owned(),
+            count: 0,
+            timed_count: 0,
+            total_duration: 0,
+            mean_duration: 0,
+            max_duration: 0,
+            steps: Vec::new(),
+        };
+
+        for duration in &[Some(10), None, Some(30)] {
+            usage.add_step(StepUsage {
+                text: "When I add 1".to_owned(),
+                location: "calc.feature:5".to_owned(),
+                duration: *duration,
+            });
+        }
+
+        assert_eq!(usage.count, 3);
+        assert_eq!(usage.total_duration, 40);
+        assert_eq!(usage.mean_duration, 20);
+        assert_eq!(usage.max_duration, 30);
+    }
+}
@@ -13,11 +13,23 @@ fn test_cucumber_features() {
 
     let config = Config {
         features_dir: &[env!("CARGO_MANIFEST_DIR"), "tests", "features"].iter().collect::<PathBuf>(),
+        feature_paths: &[],
+        include: &[],
+        exclude: &[],
+        embedded_features: None,
         output_dir: &[env!("CARGO_MANIFEST_DIR"), "target", "cucumber"].iter().collect::<PathBuf>(),
         strict: true,
         colored_output: true,
         dry_run: false,
         tags: &["not @ignore"],
+        tag_predicates: &[],
+        names: &[],
+        lines: &[],
+        rerun_file: None,
+        step_timeout: None,
+        retries: 0,
+        fail_on_flaky: true,
+        threads: None,
         execution_mode: ExecutionMode::Sequential {
             event_listeners: &[
                 &PrettyPrintListener::new(),
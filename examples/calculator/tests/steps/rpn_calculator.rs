@@ -38,10 +38,6 @@ pub fn init(scenario: &mut Scenario) {
 // #[after_scenario(order, tags)]
 // #[before_step(order, tags)]
 // #[after_step(order, tags)]
-// // hook that should be executed before the tests are run (one-only global setup hook)
-// #[before_all(order)]
-// // reverse of #before_all
-// #[after_all(order)]
 // // not sure about this one yet...
 // // #[after_configuration] taking configuration as function argument
 
@@ -23,11 +23,23 @@ fn test_cucumber_features() {
 
     let config = Config {
         features_dir: &[env!("CARGO_MANIFEST_DIR"), "tests", "features"].iter().collect::<PathBuf>(),
+        feature_paths: &[],
+        include: &[],
+        exclude: &[],
+        embedded_features: None,
         output_dir,
         strict: true,
         colored_output: true,
         dry_run: false,
         tags: &[],
+        tag_predicates: &[],
+        names: &[],
+        lines: &[],
+        rerun_file: None,
+        step_timeout: None,
+        retries: 0,
+        fail_on_flaky: true,
+        threads: None,
         execution_mode: ExecutionMode::ParallelScenarios {
             event_listeners: &[
                 &ProgressBarListener::with_style(ProgressStyle::default_bar()